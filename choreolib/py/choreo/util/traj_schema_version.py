@@ -1,2 +1,2 @@
 # Auto-generated by update_traj_schema.py
-TRAJ_SCHEMA_VERSION = 1
+TRAJ_SCHEMA_VERSION = 2
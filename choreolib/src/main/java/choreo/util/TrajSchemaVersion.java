@@ -7,7 +7,7 @@ package choreo.util;
 /** Internal autogenerated class for storing the current trajectory schema version. */
 public class TrajSchemaVersion {
   /** The current trajectory schema version. */
-  public static final int TRAJ_SCHEMA_VERSION = 1;
+  public static final int TRAJ_SCHEMA_VERSION = 2;
 
   /** Utility class. */
   private TrajSchemaVersion() {}
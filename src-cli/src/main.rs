@@ -1,16 +1,25 @@
 #![allow(dead_code)]
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::exit,
     thread::{self, JoinHandle},
 };
 
 use choreo_core::{
+    codegen::CodegenOptions,
     file_management::{self, WritingResources},
     generation::generate::generate,
+    render::RenderOptions,
+    spec::{
+        diff::TrajectoryDiff,
+        feasibility,
+        flip::Field,
+        project::ProjectFile,
+        trajectory::{CsvColumns, Precision, TrajectoryFile},
+    },
     ChoreoError,
 };
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 
 const FORMATTING_OPTIONS: &str = "Formatting Options";
 const FILE_OPTIONS: &str = "File Options";
@@ -23,6 +32,64 @@ enum CliAction {
         project_path: PathBuf,
         trajectory_names: Vec<String>,
     },
+    RenameVariable {
+        project_path: PathBuf,
+        old_name: String,
+        new_name: String,
+        dry_run: bool,
+    },
+    ExportSplits {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        output: PathBuf,
+    },
+    Resample {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        dt: f64,
+        output: PathBuf,
+    },
+    CheckFeasibility {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        tolerance: f64,
+    },
+    ExportBinary {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        precision: Precision,
+    },
+    ExportCsv {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        speed: bool,
+        module_states: bool,
+        output: PathBuf,
+    },
+    ExportWpilib {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        output: PathBuf,
+    },
+    Codegen {
+        project_path: PathBuf,
+        options: CodegenOptions,
+        output: PathBuf,
+    },
+    Stats {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+    },
+    Diff {
+        old_path: PathBuf,
+        new_path: PathBuf,
+    },
+    Render {
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        options: RenderOptions,
+        output: PathBuf,
+    },
     Error(String),
 }
 
@@ -32,6 +99,20 @@ enum CliAction {
     author = "Choreo Contributors",
     about = "Choreo CLI",
     bin_name = "Choreo",
+    group(ArgGroup::new("action").required(true).args([
+        "generate",
+        "rename_variable",
+        "export_splits",
+        "resample",
+        "check_feasibility",
+        "export_binary",
+        "export_csv",
+        "export_wpilib",
+        "codegen",
+        "stats",
+        "diff",
+        "render",
+    ])),
     before_long_help = r#"
     This CLI is still in alpha and has some quirks.
     Gui opening has been deprecated from this exe and can be done by running `Choreo.exe` directly
@@ -44,7 +125,7 @@ pub struct Cli {
         long,
         value_name = "path/to/myproject.chor",
         help_heading = FILE_OPTIONS,
-        required = true,
+        required_unless_present = "diff",
     )]
     pub chor: Option<PathBuf>,
 
@@ -66,15 +147,184 @@ pub struct Cli {
     )]
     pub all_trajectory: bool,
 
+    #[arg(
+        long,
+        value_name = "path/to/dir",
+        help_heading = FILE_OPTIONS,
+        help = "The directory to write exported files to"
+    )]
+    pub output: Option<PathBuf>,
+
     #[arg(
         long,
         short,
         requires = "chor",
         help_heading = ACTION_OPTIONS,
-        help = "Generate the provided trajectories for the project",
-        required = true,
+        help = "Generate the provided trajectories for the project"
     )]
     pub generate: bool,
+
+    #[arg(
+        long,
+        requires = "chor",
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        help_heading = ACTION_OPTIONS,
+        help = "Rename a variable and rewrite every expression that references it"
+    )]
+    pub rename_variable: Vec<String>,
+
+    #[arg(
+        long,
+        requires = "rename_variable",
+        help_heading = ACTION_OPTIONS,
+        help = "List the files that would change without writing them"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Write each split of the provided trajectories as its own file, to --output or a splits directory next to the project"
+    )]
+    pub export_splits: bool,
+
+    #[arg(
+        long,
+        requires = "chor",
+        value_name = "seconds",
+        help_heading = ACTION_OPTIONS,
+        help = "Write the provided trajectories sampled at a fixed period, to --output or a resampled directory next to the project"
+    )]
+    pub resample: Option<f64>,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Check that the samples of the provided trajectories respect the robot's limits and their constraints"
+    )]
+    pub check_feasibility: bool,
+
+    #[arg(
+        long,
+        value_name = "margin",
+        default_value_t = feasibility::DEFAULT_TOLERANCE,
+        requires = "check_feasibility",
+        help_heading = ADVANCED_OPTIONS,
+        help = "How far past a limit a sample can be before it is reported, in SI units"
+    )]
+    pub tolerance: f64,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Write the provided trajectories in the compact binary format next to their trajectory files"
+    )]
+    pub export_binary: bool,
+
+    #[arg(
+        long,
+        requires = "export_binary",
+        help_heading = ADVANCED_OPTIONS,
+        help = "Write 4 byte floats instead of 8 byte floats, halving the size of binary trajectories"
+    )]
+    pub f32: bool,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Write the samples of the provided trajectories as CSV, to --output or a csv directory next to the project"
+    )]
+    pub export_csv: bool,
+
+    #[arg(
+        long,
+        requires = "export_csv",
+        help_heading = ADVANCED_OPTIONS,
+        help = "Add a column with the speed of the robot to the CSV"
+    )]
+    pub speed: bool,
+
+    #[arg(
+        long,
+        requires = "export_csv",
+        help_heading = ADVANCED_OPTIONS,
+        help = "Add columns with the state of each swerve module to the CSV"
+    )]
+    pub module_states: bool,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Write the provided trajectories in WPILib's trajectory JSON format, to --output or a wpilib directory next to the project"
+    )]
+    pub export_wpilib: bool,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Write Java, C++ and Python constants for every trajectory and variable in the project, to --output or a codegen directory next to the project"
+    )]
+    pub codegen: bool,
+
+    #[arg(
+        long,
+        value_name = "name",
+        default_value = "ChoreoConstants",
+        requires = "codegen",
+        help_heading = ADVANCED_OPTIONS,
+        help = "The name of the generated class, namespace and files"
+    )]
+    pub class_name: String,
+
+    #[arg(
+        long,
+        value_name = "package",
+        requires = "codegen",
+        help_heading = ADVANCED_OPTIONS,
+        help = "The package of the generated Java class"
+    )]
+    pub java_package: Option<String>,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Print a table of the time, length, peak values and split durations of the provided trajectories"
+    )]
+    pub stats: bool,
+
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        help_heading = ACTION_OPTIONS,
+        help = "Print the changes to the parameters, events and samples between two versions of a trajectory file"
+    )]
+    pub diff: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "chor",
+        help_heading = ACTION_OPTIONS,
+        help = "Draw the provided trajectories on the field as SVG images, to --output or a render directory next to the project"
+    )]
+    pub render: bool,
+
+    #[arg(
+        long,
+        value_name = "year",
+        requires = "render",
+        help_heading = ADVANCED_OPTIONS,
+        help = "The competition year of the field to draw, the current year if not provided"
+    )]
+    pub year: Option<u32>,
 }
 
 impl Cli {
@@ -103,8 +353,205 @@ impl Cli {
                 };
             }
             CliAction::Error("Choreo file must be provided for generation.".to_string())
+        } else if let [old_name, new_name] = self.rename_variable.as_slice() {
+            if let Some(project_path) = self.chor {
+                return CliAction::RenameVariable {
+                    project_path,
+                    old_name: old_name.clone(),
+                    new_name: new_name.clone(),
+                    dry_run: self.dry_run,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for renaming.".to_string())
+        } else if self.export_splits {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for exporting splits.".to_string(),
+                    );
+                }
+                let output = self.output.unwrap_or_else(|| {
+                    project_path
+                        .parent()
+                        .expect("project path should have a parent directory")
+                        .join("splits")
+                });
+                return CliAction::ExportSplits {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    output,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for exporting splits.".to_string())
+        } else if let Some(dt) = self.resample {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for resampling.".to_string(),
+                    );
+                }
+                let output = self.output.unwrap_or_else(|| {
+                    project_path
+                        .parent()
+                        .expect("project path should have a parent directory")
+                        .join("resampled")
+                });
+                return CliAction::Resample {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    dt,
+                    output,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for resampling.".to_string())
+        } else if self.check_feasibility {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for checking feasibility.".to_string(),
+                    );
+                }
+                return CliAction::CheckFeasibility {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    tolerance: self.tolerance,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for checking feasibility.".to_string())
+        } else if self.export_binary {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for exporting binary trajectories."
+                            .to_string(),
+                    );
+                }
+                return CliAction::ExportBinary {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    precision: if self.f32 {
+                        Precision::F32
+                    } else {
+                        Precision::F64
+                    },
+                };
+            }
+            CliAction::Error(
+                "Choreo file must be provided for exporting binary trajectories.".to_string(),
+            )
+        } else if self.export_csv {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for exporting CSV.".to_string(),
+                    );
+                }
+                let output = self.output.unwrap_or_else(|| {
+                    project_path
+                        .parent()
+                        .expect("project path should have a parent directory")
+                        .join("csv")
+                });
+                return CliAction::ExportCsv {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    speed: self.speed,
+                    module_states: self.module_states,
+                    output,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for exporting CSV.".to_string())
+        } else if self.export_wpilib {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for exporting to WPILib.".to_string(),
+                    );
+                }
+                let output = self.output.unwrap_or_else(|| {
+                    project_path
+                        .parent()
+                        .expect("project path should have a parent directory")
+                        .join("wpilib")
+                });
+                return CliAction::ExportWpilib {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    output,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for exporting to WPILib.".to_string())
+        } else if self.codegen {
+            if let Some(project_path) = self.chor {
+                let output = self.output.unwrap_or_else(|| {
+                    project_path
+                        .parent()
+                        .expect("project path should have a parent directory")
+                        .join("codegen")
+                });
+                return CliAction::Codegen {
+                    project_path,
+                    options: CodegenOptions {
+                        name: self.class_name,
+                        java_package: self.java_package,
+                    },
+                    output,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for generating constants.".to_string())
+        } else if self.stats {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for statistics.".to_string(),
+                    );
+                }
+                return CliAction::Stats {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for statistics.".to_string())
+        } else if let [old_path, new_path] = self.diff.as_slice() {
+            CliAction::Diff {
+                old_path: old_path.clone(),
+                new_path: new_path.clone(),
+            }
+        } else if self.render {
+            if let Some(project_path) = self.chor {
+                if self.trajectory.is_empty() && !self.all_trajectory {
+                    return CliAction::Error(
+                        "Trajectories must be provided for rendering.".to_string(),
+                    );
+                }
+                let field = match self.year {
+                    Some(year) => match Field::for_year(year) {
+                        Some(field) => field,
+                        None => {
+                            return CliAction::Error(format!("There is no field for {year}."));
+                        }
+                    },
+                    None => Field::default(),
+                };
+                let output = self.output.unwrap_or_else(|| {
+                    project_path
+                        .parent()
+                        .expect("project path should have a parent directory")
+                        .join("render")
+                });
+                return CliAction::Render {
+                    project_path,
+                    trajectory_names: self.trajectory,
+                    options: RenderOptions {
+                        field,
+                        ..RenderOptions::default()
+                    },
+                    output,
+                };
+            }
+            CliAction::Error("Choreo file must be provided for rendering.".to_string())
         } else {
-            CliAction::Error("Only generate action is supported for now".to_string())
+            CliAction::Error("No action was provided".to_string())
         }
     }
 
@@ -113,21 +560,155 @@ impl Cli {
 
         let action = self.action();
 
+        let runtime = choreo_core::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to build tokio runtime");
+
         match action {
             CliAction::Generate {
                 project_path,
                 trajectory_names,
             } => {
                 tracing::info!("CLIAction is Generate");
-                choreo_core::tokio::runtime::Builder::new_current_thread()
-                    .enable_all()
-                    .build()
-                    .expect("Failed to build tokio runtime")
-                    .block_on(Self::generate_trajectories(
-                        resources,
-                        project_path,
-                        trajectory_names,
-                    ));
+                runtime.block_on(Self::generate_trajectories(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                ));
+            }
+            CliAction::RenameVariable {
+                project_path,
+                old_name,
+                new_name,
+                dry_run,
+            } => {
+                tracing::info!("CLIAction is RenameVariable");
+                runtime.block_on(Self::rename_variable(
+                    resources,
+                    project_path,
+                    old_name,
+                    new_name,
+                    dry_run,
+                ));
+            }
+            CliAction::ExportSplits {
+                project_path,
+                trajectory_names,
+                output,
+            } => {
+                tracing::info!("CLIAction is ExportSplits");
+                runtime.block_on(Self::export_splits(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    output,
+                ));
+            }
+            CliAction::Resample {
+                project_path,
+                trajectory_names,
+                dt,
+                output,
+            } => {
+                tracing::info!("CLIAction is Resample");
+                runtime.block_on(Self::resample(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    dt,
+                    output,
+                ));
+            }
+            CliAction::CheckFeasibility {
+                project_path,
+                trajectory_names,
+                tolerance,
+            } => {
+                tracing::info!("CLIAction is CheckFeasibility");
+                runtime.block_on(Self::check_feasibility(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    tolerance,
+                ));
+            }
+            CliAction::ExportBinary {
+                project_path,
+                trajectory_names,
+                precision,
+            } => {
+                tracing::info!("CLIAction is ExportBinary");
+                runtime.block_on(Self::export_binary(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    precision,
+                ));
+            }
+            CliAction::ExportCsv {
+                project_path,
+                trajectory_names,
+                speed,
+                module_states,
+                output,
+            } => {
+                tracing::info!("CLIAction is ExportCsv");
+                runtime.block_on(Self::export_csv(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    speed,
+                    module_states,
+                    output,
+                ));
+            }
+            CliAction::ExportWpilib {
+                project_path,
+                trajectory_names,
+                output,
+            } => {
+                tracing::info!("CLIAction is ExportWpilib");
+                runtime.block_on(Self::export_wpilib(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    output,
+                ));
+            }
+            CliAction::Codegen {
+                project_path,
+                options,
+                output,
+            } => {
+                tracing::info!("CLIAction is Codegen");
+                runtime.block_on(Self::codegen(resources, project_path, options, output));
+            }
+            CliAction::Stats {
+                project_path,
+                trajectory_names,
+            } => {
+                tracing::info!("CLIAction is Stats");
+                runtime.block_on(Self::stats(resources, project_path, trajectory_names));
+            }
+            CliAction::Diff { old_path, new_path } => {
+                tracing::info!("CLIAction is Diff");
+                Self::diff(&old_path, &new_path);
+            }
+            CliAction::Render {
+                project_path,
+                trajectory_names,
+                options,
+                output,
+            } => {
+                tracing::info!("CLIAction is Render");
+                runtime.block_on(Self::render(
+                    resources,
+                    project_path,
+                    trajectory_names,
+                    options,
+                    output,
+                ));
             }
             CliAction::Error(e) => {
                 tracing::error!("{}", e);
@@ -136,15 +717,10 @@ impl Cli {
         }
     }
 
-    #[allow(clippy::cast_possible_wrap)]
-    async fn generate_trajectories(
-        resources: WritingResources,
-        project_path: PathBuf,
-        mut trajectory_names: Vec<String>,
-    ) {
-        // set the deploy path to the project directory
+    /// Set the deploy path to the project directory and read the project file.
+    async fn open_project(resources: &WritingResources, project_path: &Path) -> ProjectFile {
         file_management::set_deploy_path(
-            &resources,
+            resources,
             project_path
                 .parent()
                 .expect("project path should have a parent directory")
@@ -152,9 +728,8 @@ impl Cli {
         )
         .await;
 
-        // read the project file
-        let project = file_management::read_projectfile(
-            &resources,
+        file_management::read_projectfile(
+            resources,
             project_path
                 .file_stem()
                 .expect("project path should have a file name")
@@ -163,7 +738,338 @@ impl Cli {
                 .to_string(),
         )
         .await
-        .expect("Failed to read project file");
+        .expect("Failed to read project file")
+    }
+
+    /// Read the named trajectories, or every trajectory in the project if none are named.
+    async fn read_trajectories(
+        resources: &WritingResources,
+        mut trajectory_names: Vec<String>,
+    ) -> Vec<TrajectoryFile> {
+        if trajectory_names.is_empty() {
+            trajectory_names = file_management::find_all_trajectories(resources).await;
+            trajectory_names.sort();
+        }
+
+        let mut trajectories = Vec::with_capacity(trajectory_names.len());
+        for trajectory_name in trajectory_names {
+            trajectories.push(
+                file_management::read_trajectory_file(resources, trajectory_name)
+                    .await
+                    .expect("Failed to read trajectory file"),
+            );
+        }
+        trajectories
+    }
+
+    /// Like [`Self::read_trajectories`], skipping the trajectories that have not been
+    /// generated.
+    async fn generated_trajectories(
+        resources: &WritingResources,
+        trajectory_names: Vec<String>,
+    ) -> Vec<TrajectoryFile> {
+        let mut trajectories = Self::read_trajectories(resources, trajectory_names).await;
+        trajectories.retain(|trajectory| {
+            if trajectory.trajectory.samples.is_empty() {
+                tracing::warn!("Skipping {:}, it has not been generated", trajectory.name);
+                return false;
+            }
+            Self::warn_if_outdated(trajectory);
+            true
+        });
+        trajectories
+    }
+
+    fn warn_if_outdated(trajectory: &TrajectoryFile) {
+        if !trajectory.up_to_date() {
+            tracing::warn!("{:} is not up to date with its parameters", trajectory.name);
+        }
+    }
+
+    async fn rename_variable(
+        resources: WritingResources,
+        project_path: PathBuf,
+        old_name: String,
+        new_name: String,
+        dry_run: bool,
+    ) {
+        let project = Self::open_project(&resources, &project_path).await;
+        let project_name = project.name.clone();
+        match file_management::rename_variable(&resources, project, &old_name, &new_name, dry_run)
+            .await
+        {
+            Ok(rename) => {
+                let verb = if dry_run { "Would update" } else { "Updated" };
+                tracing::info!("{verb} {project_name}.chor");
+                for trajectory in rename.trajectories {
+                    tracing::info!("{verb} {trajectory}.traj");
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to rename variable {old_name} to {new_name}: {e}");
+                exit(1);
+            }
+        }
+    }
+
+    async fn export_splits(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        output: PathBuf,
+    ) {
+        Self::open_project(&resources, &project_path).await;
+
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            match file_management::export_splits(&trajectory, &output).await {
+                Ok(files) => {
+                    for file in files {
+                        tracing::info!("Wrote {:}", file.display());
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to export splits of {:}: {:}", trajectory.name, e);
+                    exit(1);
+                }
+            }
+        }
+    }
+
+    async fn resample(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        dt: f64,
+        output: PathBuf,
+    ) {
+        Self::open_project(&resources, &project_path).await;
+
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            match file_management::export_resampled(&trajectory, dt, &output).await {
+                Ok(file) => tracing::info!("Wrote {:}", file.display()),
+                Err(e) => {
+                    tracing::error!("Failed to resample {:}: {:}", trajectory.name, e);
+                    exit(1);
+                }
+            }
+        }
+    }
+
+    async fn check_feasibility(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        tolerance: f64,
+    ) {
+        let project = Self::open_project(&resources, &project_path).await;
+
+        let mut infeasible = false;
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            let violations = feasibility::check(&trajectory, &project.config, tolerance);
+            if violations.is_empty() {
+                tracing::info!("{:} is feasible", trajectory.name);
+                continue;
+            }
+            infeasible = true;
+            tracing::error!("{:} has {:} violations", trajectory.name, violations.len());
+            for violation in violations {
+                tracing::error!("{:}: {:}", trajectory.name, violation);
+            }
+        }
+        if infeasible {
+            exit(1);
+        }
+    }
+
+    async fn export_binary(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        precision: Precision,
+    ) {
+        Self::open_project(&resources, &project_path).await;
+
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            match file_management::export_binary(&resources, &trajectory, precision).await {
+                Ok(file) => tracing::info!("Wrote {:}", file.display()),
+                Err(e) => {
+                    tracing::error!("Failed to export {:}: {:}", trajectory.name, e);
+                    exit(1);
+                }
+            }
+        }
+    }
+
+    async fn export_csv(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        speed: bool,
+        module_states: bool,
+        output: PathBuf,
+    ) {
+        let project = Self::open_project(&resources, &project_path).await;
+        let config = project.config.snapshot();
+        let columns = CsvColumns {
+            speed,
+            module_states: module_states.then_some(&config),
+        };
+
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            match file_management::export_csv(&trajectory, &columns, &output).await {
+                Ok(file) => tracing::info!("Wrote {:}", file.display()),
+                Err(e) => {
+                    tracing::error!("Failed to export {:} as CSV: {:}", trajectory.name, e);
+                    exit(1);
+                }
+            }
+        }
+    }
+
+    async fn export_wpilib(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        output: PathBuf,
+    ) {
+        Self::open_project(&resources, &project_path).await;
+
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            match file_management::export_wpilib(&trajectory, &output).await {
+                Ok(file) => tracing::info!("Wrote {:}", file.display()),
+                Err(e) => {
+                    tracing::error!("Failed to export {:} to WPILib: {:}", trajectory.name, e);
+                    exit(1);
+                }
+            }
+        }
+    }
+
+    async fn codegen(
+        resources: WritingResources,
+        project_path: PathBuf,
+        options: CodegenOptions,
+        output: PathBuf,
+    ) {
+        let project = Self::open_project(&resources, &project_path).await;
+        match file_management::export_constants(&resources, &project, &options, &output).await {
+            Ok(files) => {
+                for file in files {
+                    tracing::info!("Wrote {:}", file.display());
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to generate constants for {:}: {:}", project.name, e);
+                exit(1);
+            }
+        }
+    }
+
+    async fn stats(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+    ) {
+        Self::open_project(&resources, &project_path).await;
+
+        let mut rows = vec![[
+            "Trajectory".to_string(),
+            "Time (s)".to_string(),
+            "Length (m)".to_string(),
+            "Speed (m/s)".to_string(),
+            "Accel (m/s²)".to_string(),
+            "Omega (rad/s)".to_string(),
+            "Force (N)".to_string(),
+            "Splits (s)".to_string(),
+        ]];
+        for trajectory in Self::generated_trajectories(&resources, trajectory_names).await {
+            let stats = trajectory.trajectory.stats();
+            rows.push([
+                trajectory.name,
+                format!("{:.3}", stats.total_time),
+                format!("{:.3}", stats.path_length),
+                format!("{:.3}", stats.max_speed),
+                format!("{:.3}", stats.max_acceleration),
+                format!("{:.3}", stats.max_angular_velocity),
+                format!("{:.1}", stats.peak_module_force),
+                stats
+                    .split_durations
+                    .iter()
+                    .map(|duration| format!("{duration:.3}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]);
+        }
+
+        let widths = (0..8)
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        for row in rows {
+            let cells = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, width))| {
+                    let padding = " ".repeat(width - cell.chars().count());
+                    // Numbers are right aligned, names and split lists left aligned
+                    if i == 0 || i == 7 {
+                        format!("{cell}{padding}")
+                    } else {
+                        format!("{padding}{cell}")
+                    }
+                })
+                .collect::<Vec<_>>();
+            println!("{}", cells.join("  ").trim_end());
+        }
+    }
+
+    fn diff(old_path: &Path, new_path: &Path) {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .map_err(ChoreoError::from)
+                .and_then(|contents| TrajectoryFile::from_content(&contents))
+                .unwrap_or_else(|e| {
+                    tracing::error!("Failed to read {:}: {:}", path.display(), e);
+                    exit(1);
+                })
+        };
+        println!("{}", TrajectoryDiff::new(&read(old_path), &read(new_path)));
+    }
+
+    async fn render(
+        resources: WritingResources,
+        project_path: PathBuf,
+        trajectory_names: Vec<String>,
+        options: RenderOptions,
+        output: PathBuf,
+    ) {
+        let project = Self::open_project(&resources, &project_path).await;
+        let config = project.config.snapshot();
+
+        for trajectory in Self::read_trajectories(&resources, trajectory_names).await {
+            Self::warn_if_outdated(&trajectory);
+            match file_management::export_svg(&trajectory, &config, &options, &output).await {
+                Ok(file) => tracing::info!("Wrote {:}", file.display()),
+                Err(e) => {
+                    tracing::error!("Failed to render {:}: {:}", trajectory.name, e);
+                    exit(1);
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    async fn generate_trajectories(
+        resources: WritingResources,
+        project_path: PathBuf,
+        mut trajectory_names: Vec<String>,
+    ) {
+        let project = Self::open_project(&resources, &project_path).await;
 
         if trajectory_names.is_empty() {
             trajectory_names = file_management::find_all_trajectories(&resources).await;
@@ -0,0 +1,655 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    spec::{
+        project::{Dimension, ProjectFile},
+        trajectory::TrajectoryFile,
+    },
+    ChoreoError, ChoreoResult,
+};
+
+/// A language robot code is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Java,
+    Cpp,
+    Python,
+}
+
+impl Language {
+    /// Every supported language.
+    pub const ALL: [Language; 3] = [Language::Java, Language::Cpp, Language::Python];
+
+    /// The extension of source files in this language.
+    #[must_use]
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Language::Java => "java",
+            Language::Cpp => "h",
+            Language::Python => "py",
+        }
+    }
+}
+
+/// How to name the generated source files.
+#[derive(Debug, Clone)]
+pub struct CodegenOptions {
+    /// The name of the class, namespace or module of constants, and of the file.
+    pub name: String,
+    /// The package of the Java class, if any.
+    pub java_package: Option<String>,
+}
+
+impl CodegenOptions {
+    /// The name of the class, namespace or module as an identifier, which the files are
+    /// also named.
+    #[must_use]
+    pub fn class_name(&self) -> String {
+        identifier(&self.name)
+    }
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            name: "ChoreoConstants".to_string(),
+            java_package: None,
+        }
+    }
+}
+
+/// The constants of a trajectory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryConstants {
+    pub name: String,
+    /// The number of split sections of the trajectory.
+    pub splits: usize,
+    /// The names of its event markers, in order, without duplicates.
+    pub events: Vec<String>,
+}
+
+/// The constants of an expression variable, in SI units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableConstant {
+    pub name: String,
+    pub value: f64,
+    pub dimension: Dimension,
+}
+
+/// The constants of a pose variable, in SI units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoseConstant {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+/// Everything in a project that robot code would otherwise hard code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constants {
+    /// The name of the project the constants were generated from.
+    pub project: String,
+    pub trajectories: Vec<TrajectoryConstants>,
+    pub variables: Vec<VariableConstant>,
+    pub poses: Vec<PoseConstant>,
+}
+
+/// The SI unit of values with a dimension, for comments.
+const fn unit(dimension: Dimension) -> &'static str {
+    match dimension {
+        Dimension::Number => "",
+        Dimension::Length => "m",
+        Dimension::LinVel => "m/s",
+        Dimension::LinAcc => "m/s^2",
+        Dimension::Angle => "rad",
+        Dimension::AngVel => "rad/s",
+        Dimension::AngAcc => "rad/s^2",
+        Dimension::Time => "s",
+        Dimension::Mass => "kg",
+        Dimension::Torque => "N*m",
+        Dimension::MoI => "kg*m^2",
+    }
+}
+
+/// `name` as an identifier, with anything that can't be in one replaced by `_`.
+fn identifier(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) || identifier.is_empty() {
+        format!("_{identifier}")
+    } else {
+        identifier
+    }
+}
+
+/// The keywords of Java, C++ and Python, which can't be identifiers in one of the
+/// languages, and the WPILib types the files use, which a constant of the same name
+/// would hide.
+const RESERVED: &str = "\
+    _ abstract assert boolean break byte case catch char class const continue default do \
+    double else enum extends false final finally float for goto if implements import \
+    instanceof int interface long native new null package private protected public record \
+    return short static strictfp super switch synchronized this throw throws transient \
+    true try var void volatile while yield \
+    alignas alignof and and_eq asm auto bitand bitor bool char8_t char16_t char32_t \
+    co_await co_return co_yield compl concept consteval constexpr constinit const_cast \
+    decltype delete dynamic_cast explicit export extern friend inline mutable namespace \
+    noexcept not not_eq nullptr operator or or_eq register reinterpret_cast requires \
+    signed sizeof static_assert static_cast struct template thread_local typedef typeid \
+    typename union unsigned using virtual wchar_t xor xor_eq \
+    False None True as async await def del elif except from global in is lambda nonlocal \
+    pass raise with \
+    Pose2d Rotation2d";
+
+/// Check that `names` are distinct identifiers, none of them reserved or one of `classes`,
+/// the names of the classes they are declared in.
+fn check_identifiers<'a>(
+    kind: &str,
+    names: impl IntoIterator<Item = &'a str>,
+    classes: &[&str],
+) -> ChoreoResult<()> {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for name in names {
+        let identifier = identifier(name);
+        if RESERVED.split_whitespace().any(|word| word == identifier) {
+            return Err(ChoreoError::Export(format!(
+                "The {kind} name {name:?} is the reserved word {identifier} in code"
+            )));
+        }
+        if classes.contains(&identifier.as_str()) {
+            return Err(ChoreoError::Export(format!(
+                "The {kind} name {name:?} clashes with the class {identifier} in code"
+            )));
+        }
+        if let Some(other) = seen.insert(identifier.clone(), name) {
+            return Err(ChoreoError::Export(format!(
+                "The {kind} names {other:?} and {name:?} are both {identifier} in code"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `value` as a string literal, which is valid in all of the languages.
+fn string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{value}\""))
+}
+
+/// `value` as a floating point literal, which is valid in all of the languages.
+fn float(value: f64) -> String {
+    format!("{value:?}")
+}
+
+fn comment(dimension: Dimension) -> String {
+    match unit(dimension) {
+        "" => format!("{dimension:?}"),
+        unit => format!("{dimension:?}, {unit}"),
+    }
+}
+
+impl TrajectoryConstants {
+    /// The constants of a trajectory file.
+    ///
+    /// The split count is from the generated trajectory, or from the waypoints
+    /// marked as splits if it hasn't been generated.
+    #[must_use]
+    pub fn new(file: &TrajectoryFile) -> Self {
+        let splits = if file.trajectory.samples.is_empty() {
+            let inner = file.params.waypoints.len().saturating_sub(1);
+            1 + file
+                .params
+                .waypoints
+                .iter()
+                .take(inner)
+                .skip(1)
+                .filter(|waypoint| waypoint.split)
+                .count()
+        } else {
+            let mut splits = file.trajectory.splits.clone();
+            splits.push(0);
+            splits.sort_unstable();
+            splits.dedup();
+            splits.len()
+        };
+        let mut events: Vec<String> = Vec::new();
+        for marker in &file.events {
+            if !events.contains(&marker.name) {
+                events.push(marker.name.clone());
+            }
+        }
+        Self {
+            name: file.name.clone(),
+            splits,
+            events,
+        }
+    }
+}
+
+impl Constants {
+    /// The constants of a project and its trajectories.
+    #[must_use]
+    pub fn new(project: &ProjectFile, trajectories: &[TrajectoryFile]) -> Self {
+        Self {
+            project: project.name.clone(),
+            trajectories: trajectories.iter().map(TrajectoryConstants::new).collect(),
+            variables: project
+                .variables
+                .expressions
+                .iter()
+                .map(|(name, variable)| VariableConstant {
+                    name: name.clone(),
+                    value: variable.var.val,
+                    dimension: variable.dimension,
+                })
+                .collect(),
+            poses: project
+                .variables
+                .poses
+                .iter()
+                .map(|(name, pose)| PoseConstant {
+                    name: name.clone(),
+                    x: pose.x.val,
+                    y: pose.y.val,
+                    heading: pose.heading.val,
+                })
+                .collect(),
+        }
+    }
+
+    /// The source of a file of the constants in `language`.
+    ///
+    /// Trajectories are each a class or namespace of their name, split count and
+    /// event names, variables are floating point constants in SI units commented with
+    /// their dimension, and pose variables are WPILib `Pose2d`s.
+    ///
+    /// Names are made identifiers by replacing anything that can't be in one with `_`.
+    /// Fails if that makes two trajectories, variables or poses the same, a reserved word
+    /// in any of the languages, or the name of a class they are in, so that the constants
+    /// have the same names in every language, or if a variable or pose isn't finite,
+    /// which none of the languages has a literal for.
+    pub fn to_source(&self, language: Language, options: &CodegenOptions) -> ChoreoResult<String> {
+        self.check_identifiers(options)?;
+        self.check_values()?;
+        Ok(match language {
+            Language::Java => self.java(options),
+            Language::Cpp => self.cpp(options),
+            Language::Python => self.python(),
+        })
+    }
+
+    fn check_identifiers(&self, options: &CodegenOptions) -> ChoreoResult<()> {
+        let groups = ["Trajectories", "Variables", "Poses"];
+        check_identifiers("class", [options.name.as_str()], &groups)?;
+        let class = options.class_name();
+        check_identifiers(
+            "trajectory",
+            self.trajectories.iter().map(|t| t.name.as_str()),
+            &[&class, groups[0]],
+        )?;
+        check_identifiers(
+            "variable",
+            self.variables.iter().map(|v| v.name.as_str()),
+            &[],
+        )?;
+        check_identifiers("pose", self.poses.iter().map(|p| p.name.as_str()), &[])
+    }
+
+    fn check_values(&self) -> ChoreoResult<()> {
+        let values = self
+            .variables
+            .iter()
+            .map(|v| ("variable", &v.name, v.value))
+            .chain(
+                self.poses
+                    .iter()
+                    .flat_map(|p| [p.x, p.y, p.heading].map(|value| ("pose", &p.name, value))),
+            );
+        for (kind, name, value) in values {
+            if !value.is_finite() {
+                return Err(ChoreoError::Export(format!(
+                    "The {kind} {name:?} is {value}, which has no literal in code"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn header(&self, comment: &str) -> String {
+        format!(
+            "{comment} Generated by Choreo from {}.chor, do not edit.\n",
+            self.project
+        )
+    }
+
+    fn java(&self, options: &CodegenOptions) -> String {
+        let class = options.class_name();
+        let mut out = self.header("//");
+        if let Some(package) = &options.java_package {
+            let _ = writeln!(out, "\npackage {package};");
+        }
+        out.push_str("\nimport edu.wpi.first.math.geometry.Pose2d;\n");
+        out.push_str("import edu.wpi.first.math.geometry.Rotation2d;\n\n");
+        let _ = writeln!(out, "public final class {class} {{");
+        let _ = writeln!(out, "  private {class}() {{}}\n");
+
+        out.push_str("  public static final class Trajectories {\n");
+        for (i, trajectory) in self.trajectories.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(
+                out,
+                "    public static final class {} {{",
+                identifier(&trajectory.name)
+            );
+            let _ = writeln!(
+                out,
+                "      public static final String NAME = {};",
+                string(&trajectory.name)
+            );
+            let _ = writeln!(
+                out,
+                "      public static final int SPLITS = {};",
+                trajectory.splits
+            );
+            let events = trajectory
+                .events
+                .iter()
+                .map(|event| string(event))
+                .collect::<Vec<_>>();
+            let _ = writeln!(
+                out,
+                "      public static final String[] EVENTS = {{{}}};",
+                events.join(", ")
+            );
+            out.push_str("    }\n");
+        }
+        out.push_str("  }\n\n");
+
+        out.push_str("  public static final class Variables {\n");
+        for variable in &self.variables {
+            let _ = writeln!(
+                out,
+                "    public static final double {} = {}; // {}",
+                identifier(&variable.name),
+                float(variable.value),
+                comment(variable.dimension)
+            );
+        }
+        out.push_str("  }\n\n");
+
+        out.push_str("  public static final class Poses {\n");
+        for pose in &self.poses {
+            let _ = writeln!(
+                out,
+                "    public static final Pose2d {} = new Pose2d({}, {}, Rotation2d.fromRadians({}));",
+                identifier(&pose.name),
+                float(pose.x),
+                float(pose.y),
+                float(pose.heading)
+            );
+        }
+        out.push_str("  }\n}\n");
+        out
+    }
+
+    fn cpp(&self, options: &CodegenOptions) -> String {
+        let mut out = self.header("//");
+        out.push_str("\n#pragma once\n\n");
+        out.push_str("#include <array>\n#include <string_view>\n\n");
+        out.push_str("#include <frc/geometry/Pose2d.h>\n");
+        out.push_str("#include <units/angle.h>\n#include <units/length.h>\n\n");
+        let _ = writeln!(out, "namespace {} {{\n", options.class_name());
+
+        out.push_str("namespace trajectories {\n");
+        for trajectory in &self.trajectories {
+            let _ = writeln!(out, "namespace {} {{", identifier(&trajectory.name));
+            let _ = writeln!(
+                out,
+                "inline constexpr std::string_view kName = {};",
+                string(&trajectory.name)
+            );
+            let _ = writeln!(out, "inline constexpr int kSplits = {};", trajectory.splits);
+            let events = trajectory
+                .events
+                .iter()
+                .map(|event| string(event))
+                .collect::<Vec<_>>();
+            let _ = writeln!(
+                out,
+                "inline constexpr std::array<std::string_view, {}> kEvents{{{}}};",
+                events.len(),
+                events.join(", ")
+            );
+            let _ = writeln!(out, "}}  // namespace {}", identifier(&trajectory.name));
+        }
+        out.push_str("}  // namespace trajectories\n\n");
+
+        out.push_str("namespace variables {\n");
+        for variable in &self.variables {
+            let _ = writeln!(
+                out,
+                "inline constexpr double {} = {};  // {}",
+                identifier(&variable.name),
+                float(variable.value),
+                comment(variable.dimension)
+            );
+        }
+        out.push_str("}  // namespace variables\n\n");
+
+        out.push_str("namespace poses {\n");
+        for pose in &self.poses {
+            let _ = writeln!(
+                out,
+                "inline const frc::Pose2d {}{{units::meter_t{{{}}}, units::meter_t{{{}}}, frc::Rotation2d{{units::radian_t{{{}}}}}}};",
+                identifier(&pose.name),
+                float(pose.x),
+                float(pose.y),
+                float(pose.heading)
+            );
+        }
+        out.push_str("}  // namespace poses\n\n");
+        let _ = writeln!(out, "}}  // namespace {}", options.class_name());
+        out
+    }
+
+    fn python(&self) -> String {
+        let mut out = self.header("#");
+        out.push_str("\nfrom wpimath.geometry import Pose2d, Rotation2d\n\n\n");
+
+        out.push_str("class Trajectories:\n");
+        if self.trajectories.is_empty() {
+            out.push_str("    pass\n");
+        }
+        for (i, trajectory) in self.trajectories.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "    class {}:", identifier(&trajectory.name));
+            let _ = writeln!(out, "        NAME = {}", string(&trajectory.name));
+            let _ = writeln!(out, "        SPLITS = {}", trajectory.splits);
+            let events = trajectory
+                .events
+                .iter()
+                .map(|event| format!("{}, ", string(event)))
+                .collect::<String>();
+            let _ = writeln!(out, "        EVENTS = ({})", events.trim_end());
+        }
+
+        out.push_str("\n\nclass Variables:\n");
+        if self.variables.is_empty() {
+            out.push_str("    pass\n");
+        }
+        for variable in &self.variables {
+            let _ = writeln!(
+                out,
+                "    {} = {}  # {}",
+                identifier(&variable.name),
+                float(variable.value),
+                comment(variable.dimension)
+            );
+        }
+
+        out.push_str("\n\nclass Poses:\n");
+        if self.poses.is_empty() {
+            out.push_str("    pass\n");
+        }
+        for pose in &self.poses {
+            let _ = writeln!(
+                out,
+                "    {} = Pose2d({}, {}, Rotation2d({}))",
+                identifier(&pose.name),
+                float(pose.x),
+                float(pose.y),
+                float(pose.heading)
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::fixtures::{swerve_project, swerve_trajectory};
+    use crate::spec::trajectory::{EventMarker, EventMarkerData};
+    use crate::spec::Expr;
+
+    #[test]
+    fn constants() {
+        let project = swerve_project();
+        let mut trajectory = swerve_trajectory();
+        trajectory.name = "Auto 1".to_string();
+        let marker = EventMarker {
+            name: "intake".to_string(),
+            from: EventMarkerData {
+                target: None,
+                target_timestamp: None,
+                offset: Expr::new("0 s", 0.0),
+            },
+            event: None,
+        };
+        trajectory.events = vec![marker.clone(), marker];
+        let constants = Constants::new(&project, &[trajectory]);
+        assert_eq!(
+            constants.trajectories,
+            vec![TrajectoryConstants {
+                name: "Auto 1".to_string(),
+                splits: 1,
+                events: vec!["intake".to_string()],
+            }]
+        );
+        assert_eq!(constants.variables[0].dimension, Dimension::Number);
+        assert_eq!(constants.poses[0].name, "pose");
+
+        let options = CodegenOptions {
+            java_package: Some("frc.robot".to_string()),
+            ..CodegenOptions::default()
+        };
+        let java = constants.to_source(Language::Java, &options).unwrap();
+        assert!(java.contains("package frc.robot;"));
+        assert!(java.contains("public final class ChoreoConstants {"));
+        assert!(java.contains("public static final class Auto_1 {"));
+        assert!(java.contains("public static final String NAME = \"Auto 1\";"));
+        assert!(java.contains("public static final String[] EVENTS = {\"intake\"};"));
+        assert!(java.contains("public static final double num = 0.0; // Number"));
+        assert!(java.contains(
+            "public static final Pose2d pose = new Pose2d(0.0, 0.0, Rotation2d.fromRadians(0.0));"
+        ));
+        assert_eq!(java.matches('{').count(), java.matches('}').count());
+
+        let cpp = constants.to_source(Language::Cpp, &options).unwrap();
+        assert!(cpp.contains("namespace ChoreoConstants {"));
+        assert!(cpp.contains("inline constexpr int kSplits = 1;"));
+        assert!(cpp.contains("std::array<std::string_view, 1> kEvents{\"intake\"};"));
+        assert_eq!(cpp.matches('{').count(), cpp.matches('}').count());
+
+        let python = constants.to_source(Language::Python, &options).unwrap();
+        assert!(python.contains("    class Auto_1:\n        NAME = \"Auto 1\""));
+        assert!(python.contains("EVENTS = (\"intake\",)"));
+        assert!(python.contains("    pose = Pose2d(0.0, 0.0, Rotation2d(0.0))"));
+    }
+
+    #[test]
+    fn identifiers() {
+        let trajectory = |name: &str| TrajectoryConstants {
+            name: name.to_string(),
+            splits: 1,
+            events: vec![],
+        };
+        let constants = |names: &[&str]| Constants {
+            project: "project".to_string(),
+            trajectories: names.iter().map(|name| trajectory(name)).collect(),
+            variables: vec![],
+            poses: vec![],
+        };
+        let options = CodegenOptions::default();
+        let source = |names: &[&str], options: &CodegenOptions| {
+            constants(names).to_source(Language::Python, options)
+        };
+        assert!(source(&["Auto 1", "Auto 2"], &options).is_ok());
+        assert!(source(&["Auto 1", "Auto-1"], &options).is_err());
+        assert!(source(&["class"], &options).is_err());
+        assert!(source(&["None"], &options).is_err());
+        assert!(source(&["int"], &options).is_err());
+        assert!(source(&["ChoreoConstants"], &options).is_err());
+        assert!(source(&["Trajectories"], &options).is_err());
+
+        let poses = CodegenOptions {
+            name: "Poses".to_string(),
+            ..CodegenOptions::default()
+        };
+        assert!(source(&[], &poses).is_err());
+        let spaced = CodegenOptions {
+            name: "My Constants".to_string(),
+            ..CodegenOptions::default()
+        };
+        assert_eq!(spaced.class_name(), "My_Constants");
+        assert!(source(&["My-Constants"], &spaced).is_err());
+
+        let mut variables = constants(&[]);
+        variables.variables = ["max speed", "max_speed"]
+            .map(|name| VariableConstant {
+                name: name.to_string(),
+                value: 1.0,
+                dimension: Dimension::LinVel,
+            })
+            .to_vec();
+        assert!(variables.to_source(Language::Java, &options).is_err());
+    }
+
+    #[test]
+    fn non_finite_values() {
+        let mut constants = Constants {
+            project: "project".to_string(),
+            trajectories: vec![],
+            variables: vec![VariableConstant {
+                name: "speed".to_string(),
+                value: f64::INFINITY,
+                dimension: Dimension::LinVel,
+            }],
+            poses: vec![],
+        };
+        let options = CodegenOptions::default();
+        assert!(matches!(
+            constants.to_source(Language::Java, &options),
+            Err(ChoreoError::Export(_))
+        ));
+        constants.variables[0].value = 1.0;
+        constants.poses.push(PoseConstant {
+            name: "pose".to_string(),
+            x: 0.0,
+            y: 0.0,
+            heading: f64::NAN,
+        });
+        assert!(matches!(
+            constants.to_source(Language::Cpp, &options),
+            Err(ChoreoError::Export(_))
+        ));
+        constants.poses[0].heading = 0.0;
+        assert!(constants.to_source(Language::Python, &options).is_ok());
+    }
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use trajoptlib::error::TrajoptError;
 
+use crate::spec::expression::DimensionError;
+
 #[derive(Error, Debug, Serialize, Deserialize)]
 #[allow(missing_docs)]
 #[serde(tag = "type", content = "content")]
@@ -34,8 +36,20 @@ pub enum ChoreoError {
     TrajOpt(String),
     #[error("No Deploy Path error")]
     NoDeployPath,
+    #[error("Expression error: {0}")]
+    Expression(String),
+    #[error("Variable error: {0}")]
+    Variable(String),
+    #[error("Dimension error: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Dimension(Vec<DimensionError>),
     #[error("Heading Conflict error: waypoint {0} - {1}")]
     HeadingConflict(usize, String),
+    #[error("PathPlanner error: {0}")]
+    PathPlanner(String),
+    #[error("Stitching error: {0}")]
+    Stitch(String),
+    #[error("Export error: {0}")]
+    Export(String),
     #[error("Remote Generation Error: {0}")]
     RemoteGenerationError(Box<ChoreoError>),
 }
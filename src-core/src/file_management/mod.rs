@@ -4,7 +4,7 @@ use std::{
     thread,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::{
     fs,
     sync::{
@@ -13,11 +13,18 @@ use tokio::{
     },
 };
 
-use crate::{ChoreoError, ChoreoResult, ResultExt};
+use crate::{
+    codegen::{CodegenOptions, Constants, Language},
+    render::{render_svg, RenderOptions},
+    ChoreoError, ChoreoResult, ResultExt,
+};
 
 use dashmap::DashMap;
 
-use crate::spec::{project::ProjectFile, trajectory::TrajectoryFile};
+use crate::spec::{
+    project::{ProjectFile, RobotConfig, Variables},
+    trajectory::{BinaryTrajectory, CsvColumns, Precision, TrajectoryFile},
+};
 
 type TrajectoryFileWriterPool = Arc<DashMap<String, UnboundedSender<TrajectoryFile>>>;
 type DeployPath = Arc<Mutex<PathBuf>>;
@@ -42,6 +49,23 @@ async fn write_serializable<T: Serialize + Send>(contents: T, file: &Path) -> Ch
     Ok(())
 }
 
+/// Write `contents` to `file`, creating the directory it is in.
+async fn write_contents(contents: impl AsRef<[u8]> + Send, file: &Path) -> ChoreoResult<()> {
+    let parent = file
+        .parent()
+        .ok_or_else(|| ChoreoError::FileWrite(file.to_path_buf()))?;
+    fs::create_dir_all(parent).await?;
+    fs::write(file, contents).await?;
+    Ok(())
+}
+
+/// The path of an exported file named `name` in `dir`.
+///
+/// Unlike [`Path::with_extension`], this keeps anything after a `.` in the name.
+fn export_path(dir: &Path, name: &str, extension: &str) -> PathBuf {
+    dir.join(format!("{name}.{extension}"))
+}
+
 #[allow(unused_results)]
 fn spawn_writer_task<T: Serialize + Send + Sync + 'static>(
     file: PathBuf,
@@ -163,10 +187,8 @@ pub async fn set_deploy_path(resources: &WritingResources, path: PathBuf) {
 }
 
 pub async fn write_trajectory_file(resources: &WritingResources, trajectory_file: TrajectoryFile) {
-    let file = resources
-        .root
-        .lock()
-        .await
+    let root = resources.root.lock().await.clone();
+    let file = root
         .join(&trajectory_file.name)
         .with_extension(TrajectoryFile::EXTENSION);
 
@@ -176,6 +198,8 @@ pub async fn write_trajectory_file(resources: &WritingResources, trajectory_file
         file.display()
     );
 
+    refresh_binary(&root, &trajectory_file).await.trace_err();
+
     resources
         .trajectory_file_pool
         .entry(trajectory_file.name.clone())
@@ -192,9 +216,8 @@ pub async fn write_trajectory_file_immediately(
     resources: &WritingResources,
     trajectory_file: TrajectoryFile,
 ) -> ChoreoResult<()> {
-    let file = resources
-        .get_deploy_path()
-        .await?
+    let root = resources.get_deploy_path().await?;
+    let file = root
         .join(&trajectory_file.name)
         .with_extension(TrajectoryFile::EXTENSION);
 
@@ -204,9 +227,32 @@ pub async fn write_trajectory_file_immediately(
         file.display()
     );
 
+    refresh_binary(&root, &trajectory_file).await?;
     write_serializable(trajectory_file, &file).await
 }
 
+/// The precision of the binary trajectory at `file`, if one has been exported there.
+async fn exported_precision(file: &Path) -> ChoreoResult<Option<Precision>> {
+    if !file.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(file).await?;
+    Ok(Some(
+        BinaryTrajectory::precision(bytes.as_slice()).unwrap_or_default(),
+    ))
+}
+
+/// Write `trajectory` over the binary trajectory exported next to it in `dir`, if there
+/// is one, in the precision it was exported with, so robot code never loads a stale one.
+async fn refresh_binary(dir: &Path, trajectory: &TrajectoryFile) -> ChoreoResult<()> {
+    let file = export_path(dir, &trajectory.name, BinaryTrajectory::EXTENSION);
+    if let Some(precision) = exported_precision(&file).await? {
+        let bytes = BinaryTrajectory::from(trajectory).to_bytes(precision)?;
+        write_contents(bytes, &file).await?;
+    }
+    Ok(())
+}
+
 /// Read a trajectory from a file.
 ///
 /// The name should not include the file extension.
@@ -241,6 +287,15 @@ pub async fn delete_trajectory_file(
 
     let _ = resources.trajectory_file_pool.remove(&trajectory_file.name);
     fs::remove_file(&path).await?;
+    // an exported binary trajectory would otherwise outlive its trajectory
+    let binary = export_path(
+        &root_path,
+        &trajectory_file.name,
+        BinaryTrajectory::EXTENSION,
+    );
+    if binary.exists() {
+        fs::remove_file(&binary).await?;
+    }
 
     tracing::info!(
         "Deleted trajectory {:}.traj at {:}",
@@ -271,12 +326,24 @@ pub async fn rename_trajectory_file(
         return Err(ChoreoError::FileNotFound(Some(old_path)));
     }
 
+    // an exported binary trajectory is deleted with the old file, so it is written
+    // again under the new name in the precision it was exported with
+    let old_binary = export_path(
+        &root_path,
+        &old_trajectory_file.name,
+        BinaryTrajectory::EXTENSION,
+    );
+    let binary_precision = exported_precision(&old_binary).await?;
+
     delete_trajectory_file(resources, old_trajectory_file.clone()).await?;
 
     let old_name = old_trajectory_file.name.clone();
     old_trajectory_file.name.clone_from(&new_name);
 
     write_trajectory_file(resources, old_trajectory_file.clone()).await;
+    if let Some(precision) = binary_precision {
+        export_binary(resources, &old_trajectory_file, precision).await?;
+    }
 
     tracing::info!(
         "Renamed trajectory {old_name}.traj to {new_name}.traj at {:}",
@@ -301,6 +368,25 @@ pub async fn write_projectfile(resources: &WritingResources, project: ProjectFil
     resources.project.update(project).await;
 }
 
+pub async fn write_projectfile_immediately(
+    resources: &WritingResources,
+    project: ProjectFile,
+) -> ChoreoResult<()> {
+    let file = resources
+        .get_deploy_path()
+        .await?
+        .join(&project.name)
+        .with_extension("chor");
+
+    tracing::debug!(
+        "Writing project {:} to {:} immediately",
+        project.name,
+        file.display()
+    );
+
+    write_serializable(project, &file).await
+}
+
 pub async fn read_projectfile(
     resources: &WritingResources,
     name: String,
@@ -345,3 +431,230 @@ pub async fn find_all_trajectories(resources: &WritingResources) -> Vec<String>
     );
     out
 }
+
+/// Re-resolve the waypoints bound to pose variables in every trajectory in the deploy directory,
+/// writing back the trajectories that changed.
+///
+/// This should be called after a pose variable is edited.
+/// Returns the names of the updated trajectories, which are no longer up to date.
+pub async fn refresh_pose_links(
+    resources: &WritingResources,
+    variables: &Variables,
+) -> ChoreoResult<Vec<TrajectoryFileName>> {
+    let mut updated = vec![];
+    for name in find_all_trajectories(resources).await {
+        let mut trajectory = read_trajectory_file(resources, name.clone()).await?;
+        if trajectory.resolve_pose_links(variables)? {
+            write_trajectory_file_immediately(resources, trajectory).await?;
+            updated.push(name);
+        }
+    }
+    tracing::info!("Refreshed pose links in {:} trajectories", updated.len());
+    Ok(updated)
+}
+
+/// The outcome of [`rename_variable`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableRename {
+    /// The project with the variable renamed.
+    pub project: ProjectFile,
+    /// The names of the trajectories that reference the variable.
+    pub trajectories: Vec<TrajectoryFileName>,
+}
+
+/// Rename a variable in `project` and rewrite every expression that references it,
+/// both in the project and in all trajectories in the deploy directory.
+///
+/// If `dry_run` is set, nothing is written and the result only lists the affected files.
+pub async fn rename_variable(
+    resources: &WritingResources,
+    mut project: ProjectFile,
+    old_name: &str,
+    new_name: &str,
+    dry_run: bool,
+) -> ChoreoResult<VariableRename> {
+    project.rename_variable(old_name, new_name)?;
+    let mut renamed = vec![];
+    for name in find_all_trajectories(resources).await {
+        let mut trajectory = read_trajectory_file(resources, name.clone()).await?;
+        if trajectory.rename_variable(old_name, new_name)? {
+            if !dry_run {
+                write_trajectory_file_immediately(resources, trajectory).await?;
+            }
+            renamed.push(name);
+        }
+    }
+    if !dry_run {
+        write_projectfile_immediately(resources, project.clone()).await?;
+        tracing::info!(
+            "Renamed variable {old_name} to {new_name} in {:} and {:} trajectories",
+            project.name,
+            renamed.len()
+        );
+    }
+    Ok(VariableRename {
+        project,
+        trajectories: renamed,
+    })
+}
+
+/// Write each split section of `trajectory` as its own trajectory file in `dir`,
+/// see [`TrajectoryFile::split`].
+///
+/// `dir` should not be the deploy directory, or the sections would show up as
+/// trajectories of the project. Returns the paths of the written files.
+pub async fn export_splits(trajectory: &TrajectoryFile, dir: &Path) -> ChoreoResult<Vec<PathBuf>> {
+    let mut written = vec![];
+    for section in trajectory.split() {
+        let file = export_path(dir, &section.name, TrajectoryFile::EXTENSION);
+        write_serializable(&section, &file).await?;
+        written.push(file);
+    }
+    tracing::info!(
+        "Exported {:} splits of {:} to {:}",
+        written.len(),
+        trajectory.name,
+        dir.display()
+    );
+    Ok(written)
+}
+
+/// Write `trajectory` sampled every `dt` seconds to a trajectory file of the same name
+/// in `dir`, see [`Trajectory::resampled`](crate::spec::trajectory::Trajectory::resampled).
+///
+/// `dir` should not be the deploy directory, or the original would be overwritten.
+/// Returns the path of the written file.
+pub async fn export_resampled(
+    trajectory: &TrajectoryFile,
+    dt: f64,
+    dir: &Path,
+) -> ChoreoResult<PathBuf> {
+    let resampled = TrajectoryFile {
+        trajectory: trajectory.trajectory.resampled(dt)?,
+        ..trajectory.clone()
+    };
+    let file = export_path(dir, &trajectory.name, TrajectoryFile::EXTENSION);
+    write_serializable(&resampled, &file).await?;
+    tracing::info!(
+        "Exported {:} resampled every {:} s to {:}",
+        trajectory.name,
+        dt,
+        dir.display()
+    );
+    Ok(file)
+}
+
+/// Write `trajectory` in the binary format next to its trajectory file in the deploy
+/// directory, see [`BinaryTrajectory`].
+///
+/// Once exported, the binary file is rewritten whenever the trajectory is saved, in the
+/// same precision, and follows its trajectory file when it is renamed or deleted.
+///
+/// Returns the path of the written file.
+pub async fn export_binary(
+    resources: &WritingResources,
+    trajectory: &TrajectoryFile,
+    precision: Precision,
+) -> ChoreoResult<PathBuf> {
+    let file = export_path(
+        &resources.get_deploy_path().await?,
+        &trajectory.name,
+        BinaryTrajectory::EXTENSION,
+    );
+    let bytes = BinaryTrajectory::from(trajectory).to_bytes(precision)?;
+    write_contents(bytes, &file).await?;
+    tracing::info!(
+        "Exported {:} in the binary format to {:}",
+        trajectory.name,
+        file.display()
+    );
+    Ok(file)
+}
+
+/// Write the samples of `trajectory` to a CSV file of the same name in `dir`,
+/// see [`Trajectory::to_csv`](crate::spec::trajectory::Trajectory::to_csv).
+///
+/// Returns the path of the written file.
+pub async fn export_csv(
+    trajectory: &TrajectoryFile,
+    columns: &CsvColumns<'_>,
+    dir: &Path,
+) -> ChoreoResult<PathBuf> {
+    let csv = trajectory.trajectory.to_csv(columns)?;
+    let file = export_path(dir, &trajectory.name, "csv");
+    write_contents(csv, &file).await?;
+    tracing::info!(
+        "Exported the samples of {:} as CSV to {:}",
+        trajectory.name,
+        file.display()
+    );
+    Ok(file)
+}
+
+/// Write `trajectory` as a WPILib trajectory JSON file of the same name in `dir`,
+/// see [`Trajectory::to_wpilib`](crate::spec::trajectory::Trajectory::to_wpilib).
+///
+/// Returns the path of the written file.
+pub async fn export_wpilib(trajectory: &TrajectoryFile, dir: &Path) -> ChoreoResult<PathBuf> {
+    let file = export_path(dir, &trajectory.name, "json");
+    write_serializable(trajectory.trajectory.to_wpilib(), &file).await?;
+    tracing::info!(
+        "Exported {:} as a WPILib trajectory to {:}",
+        trajectory.name,
+        file.display()
+    );
+    Ok(file)
+}
+
+/// Write the constants of `project` and every trajectory in the deploy directory as
+/// Java, C++ and Python source files in `dir`, see [`Constants`].
+///
+/// Returns the paths of the written files.
+pub async fn export_constants(
+    resources: &WritingResources,
+    project: &ProjectFile,
+    options: &CodegenOptions,
+    dir: &Path,
+) -> ChoreoResult<Vec<PathBuf>> {
+    let mut names = find_all_trajectories(resources).await;
+    names.sort();
+    let mut trajectories = vec![];
+    for name in names {
+        trajectories.push(read_trajectory_file(resources, name).await?);
+    }
+    let constants = Constants::new(project, &trajectories);
+    // nothing is written if the names can't be constants
+    let sources = Language::ALL
+        .map(|language| Ok((language, constants.to_source(language, options)?)))
+        .into_iter()
+        .collect::<ChoreoResult<Vec<_>>>()?;
+    let mut written = vec![];
+    for (language, source) in sources {
+        let file = export_path(dir, &options.class_name(), language.extension());
+        write_contents(source, &file).await?;
+        written.push(file);
+    }
+    tracing::info!(
+        "Exported the constants of {:} and {:} trajectories to {:}",
+        project.name,
+        trajectories.len(),
+        dir.display()
+    );
+    Ok(written)
+}
+
+/// Draw `trajectory` to an SVG file of the same name in `dir`, see [`render_svg`].
+///
+/// Returns the path of the written file.
+pub async fn export_svg(
+    trajectory: &TrajectoryFile,
+    config: &RobotConfig<f64>,
+    options: &RenderOptions,
+    dir: &Path,
+) -> ChoreoResult<PathBuf> {
+    let file = export_path(dir, &trajectory.name, "svg");
+    write_contents(render_svg(trajectory, config, options), &file).await?;
+    tracing::info!("Rendered {:} to {:}", trajectory.name, file.display());
+    Ok(file)
+}
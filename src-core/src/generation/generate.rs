@@ -10,9 +10,10 @@ use super::transformers::{
     CallbackSetter, ConstraintSetter, DrivetrainAndBumpersSetter, IntervalCountSetter,
     TrajectoryFileGenerator,
 };
+use crate::spec::expression::check_dimensions;
 use crate::spec::project::ProjectFile;
 use crate::spec::trajectory::{ConstraintScope, Sample, TrajectoryFile};
-use crate::ChoreoResult;
+use crate::{ChoreoError, ChoreoResult};
 
 /**
  * A [`OnceLock`] is a synchronization primitive that can be written to
@@ -111,10 +112,18 @@ fn set_initial_guess(trajectory: &mut TrajectoryFile) {
 }
 
 pub fn generate(
-    chor: ProjectFile,
+    mut chor: ProjectFile,
     mut trajectory_file: TrajectoryFile,
     handle: i64,
 ) -> ChoreoResult<TrajectoryFile> {
+    // The stored values may be stale if the files were edited outside the GUI.
+    chor.recompute_expressions()?;
+    trajectory_file.resolve_pose_links(&chor.variables)?;
+    let dimension_errors = check_dimensions(&chor, &trajectory_file);
+    if !dimension_errors.is_empty() {
+        return Err(ChoreoError::Dimension(dimension_errors));
+    }
+    trajectory_file.recompute_expressions(&chor.variables)?;
     set_initial_guess(&mut trajectory_file);
     adjust_headings(&mut trajectory_file)?;
 
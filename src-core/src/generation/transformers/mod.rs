@@ -261,5 +261,15 @@ fn postprocess(
     path.trajectory.samples = result.to_vec();
     path.trajectory.waypoints = waypoint_times;
     path.snapshot = Some(snapshot);
+    let marker_times = path.resolve_event_markers();
+    for (marker, time) in path.events.iter().zip(marker_times) {
+        if time.is_some_and(|time| time.clamped) {
+            tracing::warn!(
+                "Event marker {:} in {:} is outside the trajectory",
+                marker.name,
+                path.name
+            );
+        }
+    }
     path
 }
@@ -24,10 +24,14 @@ mod integration_tests;
 
 pub use error::ChoreoError;
 
+/// Source files of a project's constants for robot code.
+pub mod codegen;
 /// The API for managing choreo files.
 pub mod file_management;
 /// The API for generating trajectories.
 pub mod generation;
+/// Headless SVG rendering of trajectories on the field.
+pub mod render;
 /// An implementation of the `Choreo Document Specification`.
 pub mod spec;
 
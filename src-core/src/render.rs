@@ -0,0 +1,322 @@
+use std::fmt::Write;
+
+use crate::spec::{
+    flip::Field,
+    project::RobotConfig,
+    trajectory::{ConstraintData, Sample, TrajectoryFile},
+};
+
+/// How to draw a trajectory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// The field to draw the trajectory on.
+    pub field: Field,
+    /// How many pixels one meter of the field is drawn as.
+    pub scale: f64,
+    /// The space around the field, so bumpers at its edge are drawn whole.
+    ///
+    /// Units: meters
+    pub margin: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            field: Field::default(),
+            scale: 50.0,
+            margin: 0.5,
+        }
+    }
+}
+
+/// `text` with the characters that are special in XML escaped.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The speed of the robot along the ground.
+fn speed(sample: &Sample) -> f64 {
+    match *sample {
+        Sample::Swerve { vx, vy, .. } => vx.hypot(vy),
+        Sample::DifferentialDrive { vl, vr, .. } => ((vl + vr) / 2.0).abs(),
+    }
+}
+
+/// The color of a speed, from blue when stopped to red at `max`.
+fn speed_color(speed: f64, max: f64) -> String {
+    let fraction = if max > 0.0 {
+        (speed / max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    format!("hsl({:.0},90%,50%)", (1.0 - fraction) * 240.0)
+}
+
+/// Converts field coordinates to SVG coordinates, which have y pointing down.
+struct Canvas {
+    options: RenderOptions,
+    svg: String,
+}
+
+impl Canvas {
+    fn x(&self, x: f64) -> f64 {
+        (x + self.options.margin) * self.options.scale
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        (self.options.field.width - y + self.options.margin) * self.options.scale
+    }
+
+    fn length(&self, length: f64) -> f64 {
+        length * self.options.scale
+    }
+
+    fn points(&self, points: &[(f64, f64)]) -> String {
+        points
+            .iter()
+            .map(|(x, y)| format!("{:.2},{:.2}", self.x(*x), self.y(*y)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn circle(&mut self, x: f64, y: f64, r: f64, style: &str) {
+        let (cx, cy, r) = (self.x(x), self.y(y), self.length(r));
+        let _ = writeln!(
+            self.svg,
+            r#"<circle cx="{cx:.2}" cy="{cy:.2}" r="{r:.2}" {style}/>"#
+        );
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), style: &str) {
+        let (x1, y1, x2, y2) = (self.x(from.0), self.y(from.1), self.x(to.0), self.y(to.1));
+        let _ = writeln!(
+            self.svg,
+            r#"<line x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" {style}/>"#
+        );
+    }
+
+    fn text(&mut self, x: f64, y: f64, text: &str, style: &str) {
+        let (x, y) = (self.x(x), self.y(y));
+        let _ = writeln!(
+            self.svg,
+            r#"<text x="{x:.2}" y="{y:.2}" {style}>{}</text>"#,
+            escape(text)
+        );
+    }
+}
+
+/// Draw `trajectory` on the field as an SVG image, from the blue alliance's perspective.
+///
+/// The generated path is colored by speed, from blue when stopped to red at its top
+/// speed, and the robot's bumpers are drawn at each waypoint. The geometry of enabled
+/// keep in, keep out and point at constraints is drawn under the path, and event
+/// markers over it. A trajectory that was never generated is drawn as a dashed line
+/// through its waypoints.
+#[must_use]
+pub fn render_svg(
+    trajectory: &TrajectoryFile,
+    config: &RobotConfig<f64>,
+    options: &RenderOptions,
+) -> String {
+    let field = options.field;
+    let mut canvas = Canvas {
+        options: *options,
+        svg: String::new(),
+    };
+    let (width, height) = (
+        canvas.length(field.length + 2.0 * options.margin),
+        canvas.length(field.width + 2.0 * options.margin),
+    );
+    let _ = writeln!(
+        canvas.svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.2} {height:.2}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(
+        canvas.svg,
+        r##"<rect width="100%" height="100%" fill="#1e1e1e"/>"##
+    );
+    let outline = canvas.points(&[
+        (0.0, 0.0),
+        (field.length, 0.0),
+        (field.length, field.width),
+        (0.0, field.width),
+    ]);
+    let _ = writeln!(
+        canvas.svg,
+        r##"<polygon points="{outline}" fill="#2b2b2b" stroke="#dddddd" stroke-width="2"/>"##
+    );
+
+    for constraint in trajectory.params.constraints.iter().filter(|c| c.enabled) {
+        match constraint.data.snapshot() {
+            ConstraintData::KeepInCircle { x, y, r } => canvas.circle(
+                x,
+                y,
+                r,
+                r##"fill="none" stroke="#4caf50" stroke-width="2" stroke-dasharray="6 4""##,
+            ),
+            ConstraintData::KeepOutCircle { x, y, r } => canvas.circle(
+                x,
+                y,
+                r,
+                r##"fill="#f44336" fill-opacity="0.3" stroke="#f44336" stroke-width="2""##,
+            ),
+            ConstraintData::KeepInRectangle { x, y, w, h } => {
+                let corners = canvas.points(&[(x, y), (x + w, y), (x + w, y + h), (x, y + h)]);
+                let _ = writeln!(
+                    canvas.svg,
+                    r##"<polygon points="{corners}" fill="none" stroke="#4caf50" stroke-width="2" stroke-dasharray="6 4"/>"##
+                );
+            }
+            ConstraintData::PointAt { x, y, .. } => {
+                let arm = 0.2;
+                let style = r##"stroke="#ff9800" stroke-width="2""##;
+                canvas.line((x - arm, y), (x + arm, y), style);
+                canvas.line((x, y - arm), (x, y + arm), style);
+                canvas.circle(
+                    x,
+                    y,
+                    arm / 2.0,
+                    r##"fill="none" stroke="#ff9800" stroke-width="2""##,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let samples = &trajectory.trajectory.samples;
+    if samples.is_empty() {
+        let waypoints = trajectory
+            .params
+            .waypoints
+            .iter()
+            .map(|waypoint| (waypoint.x.val, waypoint.y.val))
+            .collect::<Vec<_>>();
+        let points = canvas.points(&waypoints);
+        let _ = writeln!(
+            canvas.svg,
+            r##"<polyline points="{points}" fill="none" stroke="#9e9e9e" stroke-width="3" stroke-dasharray="8 6"/>"##
+        );
+    } else {
+        let max_speed = samples.iter().map(speed).fold(0.0, f64::max);
+        for pair in samples.windows(2) {
+            let (from, to) = (pair[0].pose(), pair[1].pose());
+            let color = speed_color((speed(&pair[0]) + speed(&pair[1])) / 2.0, max_speed);
+            canvas.line(
+                (from.x, from.y),
+                (to.x, to.y),
+                &format!(r#"stroke="{color}" stroke-width="3" stroke-linecap="round""#),
+            );
+        }
+    }
+
+    // Where the robot is at each waypoint, as generated if it has been
+    let poses = trajectory
+        .params
+        .waypoints
+        .iter()
+        .enumerate()
+        .map(|(i, waypoint)| {
+            match trajectory
+                .trajectory
+                .waypoints
+                .get(i)
+                .and_then(|t| trajectory.trajectory.sample_at(*t))
+            {
+                Some(sample) => {
+                    let pose = sample.pose();
+                    (pose.x, pose.y, pose.heading)
+                }
+                None => (waypoint.x.val, waypoint.y.val, waypoint.heading.val),
+            }
+        })
+        .collect::<Vec<_>>();
+    for (x, y, heading) in poses {
+        let corners = canvas.points(&config.bumper.corners(x, y, heading));
+        let _ = writeln!(
+            canvas.svg,
+            r##"<polygon points="{corners}" fill="#ffffff" fill-opacity="0.1" stroke="#ffffff" stroke-width="1.5"/>"##
+        );
+        // A line to the front of the robot, so its heading can be seen
+        let (sin, cos) = heading.sin_cos();
+        canvas.line(
+            (x, y),
+            (x + config.bumper.front * cos, y + config.bumper.front * sin),
+            r##"stroke="#ffffff" stroke-width="1.5""##,
+        );
+    }
+
+    for marker in &trajectory.events {
+        let Some(sample) = trajectory
+            .event_marker_time(marker)
+            .and_then(|time| trajectory.trajectory.sample_at(time.timestamp))
+        else {
+            continue;
+        };
+        let pose = sample.pose();
+        canvas.circle(
+            pose.x,
+            pose.y,
+            0.08,
+            r##"fill="#ffeb3b" stroke="#000000" stroke-width="1""##,
+        );
+        canvas.text(
+            pose.x + 0.12,
+            pose.y + 0.12,
+            &marker.name,
+            r##"fill="#ffeb3b""##,
+        );
+    }
+
+    canvas.text(
+        -options.margin / 2.0,
+        field.width + options.margin / 2.0,
+        &trajectory.name,
+        r##"fill="#ffffff" font-size="16" dominant-baseline="middle""##,
+    );
+    canvas.svg.push_str("</svg>\n");
+    canvas.svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::fixtures::{swerve_config, swerve_trajectory};
+
+    #[test]
+    fn render() {
+        let config = swerve_config();
+        let mut trajectory = swerve_trajectory();
+        trajectory.name = "A & B".to_string();
+        let svg = render_svg(&trajectory, &config, &RenderOptions::default());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r#"width="927" height="453""#));
+        assert!(svg.contains(">A &amp; B</text>"));
+
+        let count = |pattern: &str| svg.matches(pattern).count();
+        let waypoints = trajectory.params.waypoints.len();
+        let samples = trajectory.trajectory.samples.len();
+        // The field outline, a keep in rectangle and a bumper per waypoint
+        assert_eq!(count("<polygon "), 2 + waypoints);
+        // Path segments, heading lines and the point at crosshair
+        assert_eq!(count("<line "), samples - 1 + waypoints + 2);
+        // Keep in and keep out circles, the point at target and event markers
+        let markers = trajectory
+            .events
+            .iter()
+            .filter(|marker| trajectory.event_marker_time(marker).is_some())
+            .count();
+        assert_eq!(count("<circle "), 3 + markers);
+        assert_eq!(count("hsl("), samples - 1);
+
+        trajectory.trajectory.samples.clear();
+        let svg = render_svg(&trajectory, &config, &RenderOptions::default());
+        assert_eq!(svg.matches("<polyline ").count(), 1);
+        assert!(!svg.contains("hsl("));
+        assert_eq!(speed_color(0.0, 2.0), "hsl(240,90%,50%)");
+        assert_eq!(speed_color(2.0, 2.0), "hsl(0,90%,50%)");
+    }
+}
@@ -0,0 +1,467 @@
+//! A semantic diff between two versions of a trajectory file, so a regenerated
+//! trajectory can be reviewed without reading every changed sample.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::trajectory::{Sample, Trajectory, TrajectoryFile};
+use crate::generation::angle_modulus;
+
+/// An item of a trajectory file that can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "index", rename_all = "camelCase")]
+pub enum Item {
+    /// The waypoint at this index in the parameters.
+    Waypoint(usize),
+    /// The constraint at this index in the parameters.
+    Constraint(usize),
+    /// The event marker at this index.
+    Event(usize),
+    /// The target dt of the parameters.
+    TargetDt,
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Item::Waypoint(index) => write!(f, "waypoint {index}"),
+            Item::Constraint(index) => write!(f, "constraint {index}"),
+            Item::Event(index) => write!(f, "event {index}"),
+            Item::TargetDt => write!(f, "target dt"),
+        }
+    }
+}
+
+/// A field of an item with a different value in the new file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// The path to the field as it is named in the file, like `data.props.max`.
+    ///
+    /// Empty if the item itself is the value, as for the target dt.
+    pub field: String,
+    /// The old value, or `None` if the field was added.
+    pub old: Option<Value>,
+    /// The new value, or `None` if the field was removed.
+    pub new: Option<Value>,
+}
+
+/// How an item changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "fields", rename_all = "camelCase")]
+pub enum Change {
+    /// The item is only in the new file.
+    Added,
+    /// The item is only in the old file.
+    Removed,
+    /// The item is in both files, with these fields changed.
+    Changed(Vec<FieldChange>),
+}
+
+/// A change to an item of the parameters or the event markers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemChange {
+    pub item: Item,
+    pub change: Change,
+}
+
+/// A summary of how the generated samples changed.
+///
+/// Deviations are the largest between the two trajectories at the same fraction of
+/// their total time, so a trajectory that only got faster or slower has no position
+/// or heading deviation, but its velocities still deviate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleDiff {
+    pub old_sample_count: usize,
+    pub new_sample_count: usize,
+    /// How much longer the new trajectory takes.
+    ///
+    /// Units: seconds
+    pub total_time_delta: f64,
+    /// The largest distance between the positions of the robot.
+    ///
+    /// Units: meters
+    pub max_position_deviation: f64,
+    /// The largest difference between the headings of the robot.
+    ///
+    /// Units: radians
+    pub max_heading_deviation: f64,
+    /// The largest difference between the velocities of the robot along the ground.
+    ///
+    /// Units: meters / second
+    pub max_velocity_deviation: f64,
+    /// The largest difference between the angular velocities of the robot.
+    ///
+    /// Units: radians / second
+    pub max_angular_velocity_deviation: f64,
+}
+
+/// The semantic differences between two versions of a trajectory file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrajectoryDiff {
+    /// Changes to waypoints, constraints, event markers and the target dt, in that order.
+    pub changes: Vec<ItemChange>,
+    /// How the samples changed, or `None` if either file was never generated.
+    pub samples: Option<SampleDiff>,
+}
+
+/// An expression, serialized as `{ exp, val }`, is compared as a whole rather than
+/// as two fields.
+fn is_expr(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|object| object.len() == 2 && object.contains_key("exp"))
+}
+
+/// Add the fields under `path` that differ between `old` and `new` to `changes`.
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields))
+            if !is_expr(old) && !is_expr(new) =>
+        {
+            let join = |key: &str| {
+                if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{path}.{key}")
+                }
+            };
+            for (key, old_value) in old_fields {
+                match new_fields.get(key) {
+                    Some(new_value) => diff_values(&join(key), old_value, new_value, changes),
+                    None => changes.push(FieldChange {
+                        field: join(key),
+                        old: Some(old_value.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new_value) in new_fields {
+                if !old_fields.contains_key(key) {
+                    changes.push(FieldChange {
+                        field: join(key),
+                        old: None,
+                        new: Some(new_value.clone()),
+                    });
+                }
+            }
+        }
+        _ => changes.push(FieldChange {
+            field: path.to_string(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+/// The changes between two lists of items, matched by index.
+fn diff_lists<T: Serialize>(
+    old: &[T],
+    new: &[T],
+    item: fn(usize) -> Item,
+    changes: &mut Vec<ItemChange>,
+) {
+    for index in 0..old.len().max(new.len()) {
+        let change = match (old.get(index), new.get(index)) {
+            (Some(old), Some(new)) => {
+                let mut fields = Vec::new();
+                diff_values(
+                    "",
+                    &serde_json::to_value(old).unwrap_or_default(),
+                    &serde_json::to_value(new).unwrap_or_default(),
+                    &mut fields,
+                );
+                if fields.is_empty() {
+                    continue;
+                }
+                Change::Changed(fields)
+            }
+            (None, _) => Change::Added,
+            (_, None) => Change::Removed,
+        };
+        changes.push(ItemChange {
+            item: item(index),
+            change,
+        });
+    }
+}
+
+/// The velocity of the robot along the ground, relative to the field.
+fn velocity(sample: &Sample) -> (f64, f64) {
+    match *sample {
+        Sample::Swerve { vx, vy, .. } => (vx, vy),
+        Sample::DifferentialDrive {
+            heading, vl, vr, ..
+        } => {
+            let (sin, cos) = heading.sin_cos();
+            let speed = (vl + vr) / 2.0;
+            (speed * cos, speed * sin)
+        }
+    }
+}
+
+const fn omega(sample: &Sample) -> f64 {
+    match *sample {
+        Sample::Swerve { omega, .. } | Sample::DifferentialDrive { omega, .. } => omega,
+    }
+}
+
+/// The largest deviations of `other` from each sample of `samples`, at the same
+/// fraction of their total times.
+fn deviations(samples: &Trajectory, other: &Trajectory) -> [f64; 4] {
+    let (duration, other_duration) = (samples.total_time(), other.total_time());
+    samples
+        .samples
+        .iter()
+        .filter_map(|sample| {
+            let t = if duration > 0.0 {
+                sample.t() * (other_duration / duration)
+            } else {
+                0.0
+            };
+            let aligned = other.sample_at(t)?;
+            let (pose, other_pose) = (sample.pose(), aligned.pose());
+            let (v, other_v) = (velocity(sample), velocity(&aligned));
+            Some([
+                (pose.x - other_pose.x).hypot(pose.y - other_pose.y),
+                angle_modulus(pose.heading - other_pose.heading).abs(),
+                (v.0 - other_v.0).hypot(v.1 - other_v.1),
+                (omega(sample) - omega(&aligned)).abs(),
+            ])
+        })
+        .fold([0.0; 4], |max, deviation| {
+            [0, 1, 2, 3].map(|i| f64::max(max[i], deviation[i]))
+        })
+}
+
+impl SampleDiff {
+    /// The summary of how the samples changed from `old` to `new`, or `None`
+    /// if either has no samples.
+    #[must_use]
+    pub fn new(old: &Trajectory, new: &Trajectory) -> Option<Self> {
+        if old.samples.is_empty() || new.samples.is_empty() {
+            return None;
+        }
+        // Both ways, so a sample in either trajectory can't fall between the samples
+        // of the other
+        let (forward, backward) = (deviations(old, new), deviations(new, old));
+        let [position, heading, velocity, angular_velocity] =
+            [0, 1, 2, 3].map(|i| f64::max(forward[i], backward[i]));
+        Some(Self {
+            old_sample_count: old.samples.len(),
+            new_sample_count: new.samples.len(),
+            total_time_delta: new.total_time() - old.total_time(),
+            max_position_deviation: position,
+            max_heading_deviation: heading,
+            max_velocity_deviation: velocity,
+            max_angular_velocity_deviation: angular_velocity,
+        })
+    }
+}
+
+impl TrajectoryDiff {
+    /// The differences between the `old` and `new` versions of a trajectory file.
+    ///
+    /// Waypoints, constraints and event markers are matched by index, and compared
+    /// field by field, with expressions compared by both their text and value.
+    #[must_use]
+    pub fn new(old: &TrajectoryFile, new: &TrajectoryFile) -> Self {
+        let mut changes = Vec::new();
+        diff_lists(
+            &old.params.waypoints,
+            &new.params.waypoints,
+            Item::Waypoint,
+            &mut changes,
+        );
+        diff_lists(
+            &old.params.constraints,
+            &new.params.constraints,
+            Item::Constraint,
+            &mut changes,
+        );
+        diff_lists(&old.events, &new.events, Item::Event, &mut changes);
+        let mut fields = Vec::new();
+        diff_values(
+            "",
+            &serde_json::to_value(&old.params.target_dt).unwrap_or_default(),
+            &serde_json::to_value(&new.params.target_dt).unwrap_or_default(),
+            &mut fields,
+        );
+        if !fields.is_empty() {
+            changes.push(ItemChange {
+                item: Item::TargetDt,
+                change: Change::Changed(fields),
+            });
+        }
+        Self {
+            changes,
+            samples: SampleDiff::new(&old.trajectory, &new.trajectory),
+        }
+    }
+
+    /// Whether the parameters and event markers are the same, and the samples
+    /// are the same or missing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+            && self.samples.as_ref().is_none_or(|samples| {
+                samples.old_sample_count == samples.new_sample_count
+                    && samples.total_time_delta == 0.0
+                    && samples.max_position_deviation == 0.0
+                    && samples.max_heading_deviation == 0.0
+                    && samples.max_velocity_deviation == 0.0
+                    && samples.max_angular_velocity_deviation == 0.0
+            })
+    }
+}
+
+/// A value as it would be read in the file, with expressions as their text.
+fn describe(value: Option<&Value>) -> String {
+    match value {
+        None => "nothing".to_string(),
+        Some(value) => match value.get("exp").and_then(Value::as_str) {
+            Some(exp) => exp.to_string(),
+            None => value.to_string(),
+        },
+    }
+}
+
+impl fmt::Display for ItemChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.change {
+            Change::Added => write!(f, "+ {}", self.item),
+            Change::Removed => write!(f, "- {}", self.item),
+            Change::Changed(fields) => {
+                write!(f, "~ {}", self.item)?;
+                for field in fields {
+                    let name = if field.field.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{}: ", field.field)
+                    };
+                    write!(
+                        f,
+                        "\n    {name}{} -> {}",
+                        describe(field.old.as_ref()),
+                        describe(field.new.as_ref())
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for SampleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "samples: {} -> {}",
+            self.old_sample_count, self.new_sample_count
+        )?;
+        writeln!(f, "total time: {:+.3} s", self.total_time_delta)?;
+        writeln!(
+            f,
+            "max position deviation: {:.3} m",
+            self.max_position_deviation
+        )?;
+        writeln!(
+            f,
+            "max heading deviation: {:.3} rad",
+            self.max_heading_deviation
+        )?;
+        writeln!(
+            f,
+            "max velocity deviation: {:.3} m/s",
+            self.max_velocity_deviation
+        )?;
+        write!(
+            f,
+            "max angular velocity deviation: {:.3} rad/s",
+            self.max_angular_velocity_deviation
+        )
+    }
+}
+
+impl fmt::Display for TrajectoryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        match &self.samples {
+            Some(samples) => write!(f, "{samples}"),
+            None => write!(f, "samples: not generated in both versions"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{fixtures::swerve_trajectory, trajectory::ConstraintData, Expr};
+
+    #[test]
+    fn diff() {
+        let old = swerve_trajectory();
+        assert!(TrajectoryDiff::new(&old, &old).is_empty());
+
+        let mut new = old.clone();
+        new.params.waypoints[1].x = Expr::new("3 m", 3.0);
+        new.params.constraints.truncate(4);
+        new.params.constraints[3].data = ConstraintData::MaxVelocity {
+            max: Expr::new("2 m / s", 2.0),
+        };
+        let diff = TrajectoryDiff::new(&old, &new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.changes.len(), 2 + 6);
+        assert_eq!(diff.changes[0].item, Item::Waypoint(1));
+        let Change::Changed(fields) = &diff.changes[0].change else {
+            panic!("expected a changed waypoint");
+        };
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field, "x");
+        assert_eq!(describe(fields[0].old.as_ref()), "2 m");
+        assert_eq!(describe(fields[0].new.as_ref()), "3 m");
+        assert_eq!(diff.changes[1].item, Item::Constraint(3));
+        let Change::Changed(fields) = &diff.changes[1].change else {
+            panic!("expected a changed constraint");
+        };
+        assert_eq!(fields[0].field, "data.props.max");
+        assert_eq!(diff.changes[2].item, Item::Constraint(4));
+        assert_eq!(diff.changes[2].change, Change::Removed);
+        assert!(diff.to_string().contains("    data.props.max: "));
+
+        // The samples are unchanged
+        let samples = diff.samples.unwrap();
+        assert_eq!(samples.total_time_delta, 0.0);
+        assert_eq!(samples.max_position_deviation, 0.0);
+
+        let resampled = old.trajectory.resampled(0.01).unwrap();
+        let samples = SampleDiff::new(&old.trajectory, &resampled).unwrap();
+        assert_ne!(samples.old_sample_count, samples.new_sample_count);
+        // Only as far apart as interpolating between interpolated samples
+        assert!(samples.max_position_deviation < 1e-4);
+        assert!(samples.max_velocity_deviation < 0.01);
+
+        let mut moved = old.trajectory.clone();
+        for sample in &mut moved.samples {
+            if let Sample::Swerve { y, .. } = sample {
+                *y += 0.5;
+            }
+        }
+        let samples = SampleDiff::new(&old.trajectory, &moved).unwrap();
+        assert!((samples.max_position_deviation - 0.5).abs() < 1e-9);
+        assert_eq!(samples.max_velocity_deviation, 0.0);
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::spec::{
+    project::{Dimension, ProjectFile, Variables},
+    trajectory::TrajectoryFile,
+    Expr,
+};
+
+use super::{expression_message, BaseDimensions, Scope};
+
+/// An expression whose dimensions don't match the field it is used in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DimensionError {
+    /// The JSON path of the expression within its file, e.g. `params.waypoints.0.x`.
+    pub path: String,
+    /// The offending expression string.
+    pub exp: String,
+    /// The dimension the field requires.
+    pub expected: Dimension,
+    /// The dimensions the expression evaluated to,
+    /// or `None` if it could not be evaluated at all.
+    pub found: Option<BaseDimensions>,
+    /// A human readable description of the problem.
+    pub message: String,
+}
+
+impl Display for DimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.path, self.exp, self.message)
+    }
+}
+
+fn check(scope: &Scope, path: &str, expected: Dimension, expr: &Expr) -> Option<DimensionError> {
+    let error = |found, message| DimensionError {
+        path: path.to_string(),
+        exp: expr.exp.clone(),
+        expected,
+        found,
+        message,
+    };
+    match expr.evaluate(scope) {
+        Ok(quantity) if quantity.dimensions == expected.base_dimensions() => None,
+        Ok(quantity) => Some(error(
+            Some(quantity.dimensions),
+            format!(
+                "expected {expected:?} ({}), found {}",
+                expected.base_dimensions(),
+                quantity.dimensions
+            ),
+        )),
+        Err(e) => Some(error(None, expression_message(&e))),
+    }
+}
+
+/// Check that every expression in the project, including the variables,
+/// evaluates to the dimension of the field it is stored in.
+pub fn check_project_dimensions(project: &ProjectFile) -> Vec<DimensionError> {
+    let scope = Scope::new(&project.variables);
+    let mut errors = Vec::new();
+    project.for_each_expr(|path, expected, expr| {
+        errors.extend(check(&scope, path, expected, expr));
+    });
+    errors
+}
+
+/// Check that every expression in the trajectory evaluates to the dimension of the field
+/// it is stored in, resolving variable references against the project `variables`.
+pub fn check_trajectory_dimensions(
+    trajectory: &TrajectoryFile,
+    variables: &Variables,
+) -> Vec<DimensionError> {
+    let scope = Scope::new(variables);
+    let mut errors = Vec::new();
+    trajectory.for_each_expr(|path, expected, expr| {
+        errors.extend(check(&scope, path, expected, expr));
+    });
+    errors
+}
+
+/// Run [`check_project_dimensions`] and [`check_trajectory_dimensions`] together.
+///
+/// Project paths start with `variables` or `config`,
+/// trajectory paths start with `params` or `events`.
+pub fn check_dimensions(project: &ProjectFile, trajectory: &TrajectoryFile) -> Vec<DimensionError> {
+    let mut errors = check_project_dimensions(project);
+    errors.extend(check_trajectory_dimensions(trajectory, &project.variables));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{
+        fixtures::swerve_trajectory,
+        project::Variable,
+        trajectory::{Constraint, ConstraintData, WaypointID},
+    };
+
+    #[test]
+    fn default_project_is_consistent() {
+        assert!(check_project_dimensions(&ProjectFile::default()).is_empty());
+    }
+
+    #[test]
+    fn reports_paths() {
+        let mut project = ProjectFile::default();
+        project.config.mass = Expr::new("150 m", 150.0);
+        project.variables.expressions.insert(
+            "spin".to_string(),
+            Variable {
+                dimension: Dimension::Angle,
+                var: Expr::new("90 deg", 0.0),
+            },
+        );
+        let errors = check_project_dimensions(&project);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "config.mass");
+        assert_eq!(errors[0].expected, Dimension::Mass);
+
+        let mut trajectory = swerve_trajectory();
+        trajectory.params.waypoints[0].x = Expr::new("spin", 0.0);
+        trajectory.params.constraints.push(Constraint {
+            from: WaypointID::First,
+            to: None,
+            enabled: true,
+            data: ConstraintData::MaxVelocity {
+                max: Expr::new("2 m / s ^ 2", 0.0),
+            },
+        });
+        let errors = check_trajectory_dimensions(&trajectory, &project.variables);
+        let paths = errors.iter().map(|e| e.path.as_str()).collect::<Vec<_>>();
+        let last = trajectory.params.constraints.len() - 1;
+        assert_eq!(
+            paths,
+            vec![
+                "params.waypoints.0.x".to_string(),
+                format!("params.constraints.{last}.data.props.max")
+            ]
+        );
+    }
+}
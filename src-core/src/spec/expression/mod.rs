@@ -0,0 +1,576 @@
+//! A native evaluator for the unit-aware expressions stored in [`Expr::exp`].
+//!
+//! The Choreo GUI parses and evaluates these expressions with `mathjs` and stores the result
+//! in [`Expr::val`]. This module understands the same syntax and unit vocabulary, so files
+//! that were edited outside the GUI can have their values recomputed before generation.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use crate::{
+    spec::{
+        project::{ProjectFile, Variables},
+        trajectory::TrajectoryFile,
+        Expr,
+    },
+    ChoreoError, ChoreoResult,
+};
+
+mod dimensions;
+mod parser;
+mod rename;
+mod units;
+mod walk;
+
+pub use dimensions::{
+    check_dimensions, check_project_dimensions, check_trajectory_dimensions, DimensionError,
+};
+pub use parser::{BinaryOp, Node};
+pub use rename::rename_symbol;
+
+/// The exponents of the SI base dimensions of a quantity.
+///
+/// Angle is treated as a base dimension, as it is in `mathjs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BaseDimensions {
+    pub length: i8,
+    pub mass: i8,
+    pub time: i8,
+    pub angle: i8,
+}
+
+impl BaseDimensions {
+    /// The dimensions of a plain number.
+    pub const NONE: BaseDimensions = BaseDimensions::new(0, 0, 0, 0);
+
+    #[must_use]
+    pub const fn new(length: i8, mass: i8, time: i8, angle: i8) -> Self {
+        Self {
+            length,
+            mass,
+            time,
+            angle,
+        }
+    }
+
+    /// Whether these are the dimensions of a plain number.
+    #[must_use]
+    pub fn is_dimensionless(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    fn combine(self, other: Self, f: impl Fn(i8, i8) -> i8) -> Self {
+        Self::new(
+            f(self.length, other.length),
+            f(self.mass, other.mass),
+            f(self.time, other.time),
+            f(self.angle, other.angle),
+        )
+    }
+
+    fn scale(self, exponent: f64) -> Option<Self> {
+        let scale_one = |d: i8| {
+            let scaled = f64::from(d) * exponent;
+            #[allow(clippy::cast_possible_truncation)]
+            ((scaled - scaled.round()).abs() < 1e-9).then_some(scaled.round() as i8)
+        };
+        Some(Self::new(
+            scale_one(self.length)?,
+            scale_one(self.mass)?,
+            scale_one(self.time)?,
+            scale_one(self.angle)?,
+        ))
+    }
+}
+
+impl Display for BaseDimensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "dimensionless");
+        }
+        let parts = [
+            ("m", self.length),
+            ("kg", self.mass),
+            ("s", self.time),
+            ("rad", self.angle),
+        ]
+        .into_iter()
+        .filter(|(_, exponent)| *exponent != 0)
+        .map(|(unit, exponent)| {
+            if exponent == 1 {
+                unit.to_string()
+            } else {
+                format!("{unit}^{exponent}")
+            }
+        })
+        .collect::<Vec<_>>();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// The result of evaluating an expression: a value in SI base units and its dimensions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub dimensions: BaseDimensions,
+}
+
+impl Quantity {
+    #[must_use]
+    pub const fn number(value: f64) -> Self {
+        Self {
+            value,
+            dimensions: BaseDimensions::NONE,
+        }
+    }
+
+    fn same_dimensions(self, other: Self, operation: &str) -> ChoreoResult<BaseDimensions> {
+        if self.dimensions == other.dimensions {
+            Ok(self.dimensions)
+        } else {
+            Err(ChoreoError::Expression(format!(
+                "Cannot {operation} {} and {}",
+                self.dimensions, other.dimensions
+            )))
+        }
+    }
+
+    fn expect_number(self, function: &str) -> ChoreoResult<f64> {
+        if self.dimensions.is_dimensionless() {
+            Ok(self.value)
+        } else {
+            Err(ChoreoError::Expression(format!(
+                "{function} expects a number, got {}",
+                self.dimensions
+            )))
+        }
+    }
+
+    fn pow(self, exponent: Self) -> ChoreoResult<Self> {
+        let exponent = exponent.expect_number("Exponentiation")?;
+        let dimensions = self.dimensions.scale(exponent).ok_or_else(|| {
+            ChoreoError::Expression(format!(
+                "Cannot raise {} to the power {exponent}",
+                self.dimensions
+            ))
+        })?;
+        Ok(Self {
+            value: self.value.powf(exponent),
+            dimensions,
+        })
+    }
+}
+
+/// Resolves references to [`Variables`] while evaluating expressions.
+///
+/// Variable values are evaluated from their own expressions on first use and cached.
+#[allow(missing_debug_implementations)]
+pub struct Scope<'a> {
+    variables: Option<&'a Variables>,
+    cache: RefCell<HashMap<String, Quantity>>,
+    evaluating: RefCell<Vec<String>>,
+}
+
+impl<'a> Scope<'a> {
+    /// A scope that resolves references against `variables`.
+    #[must_use]
+    pub fn new(variables: &'a Variables) -> Self {
+        Self {
+            variables: Some(variables),
+            cache: RefCell::new(HashMap::new()),
+            evaluating: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// A scope without any variables.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            variables: None,
+            cache: RefCell::new(HashMap::new()),
+            evaluating: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Evaluate an expression string in this scope.
+    pub fn evaluate(&self, exp: &str) -> ChoreoResult<Quantity> {
+        self.evaluate_node(&Node::parse(exp)?)
+    }
+
+    fn resolve(&self, key: String, expr: &Expr) -> ChoreoResult<Quantity> {
+        if let Some(quantity) = self.cache.borrow().get(&key) {
+            return Ok(*quantity);
+        }
+        if self.evaluating.borrow().contains(&key) {
+            return Err(ChoreoError::Expression(format!(
+                "Circular reference to variable {key}"
+            )));
+        }
+        self.evaluating.borrow_mut().push(key.clone());
+        let result = self.evaluate(&expr.exp);
+        self.evaluating.borrow_mut().pop();
+        let quantity = result.map_err(|e| {
+            ChoreoError::Expression(format!("In variable {key}: {}", expression_message(&e)))
+        })?;
+        self.cache.borrow_mut().insert(key, quantity);
+        Ok(quantity)
+    }
+
+    fn symbol(&self, name: &str) -> ChoreoResult<Quantity> {
+        if let Some(variable) = self.variables.and_then(|v| v.expressions.get(name)) {
+            return self.resolve(name.to_string(), &variable.var);
+        }
+        if let Some(value) = units::constant(name) {
+            return Ok(Quantity::number(value));
+        }
+        units::lookup(name)
+            .ok_or_else(|| ChoreoError::Expression(format!("Undefined symbol {name}")))
+    }
+
+    fn accessor(&self, object: &str, property: &str) -> ChoreoResult<Quantity> {
+        let pose = self
+            .variables
+            .and_then(|v| v.poses.get(object))
+            .ok_or_else(|| ChoreoError::Expression(format!("Undefined pose {object}")))?;
+        let expr = match property {
+            "x" => &pose.x,
+            "y" => &pose.y,
+            "heading" => &pose.heading,
+            _ => {
+                return Err(ChoreoError::Expression(format!(
+                    "Pose {object} has no property {property}"
+                )))
+            }
+        };
+        self.resolve(format!("{object}.{property}"), expr)
+    }
+
+    fn evaluate_node(&self, node: &Node) -> ChoreoResult<Quantity> {
+        match node {
+            Node::Number(value) => Ok(Quantity::number(*value)),
+            Node::Symbol(name) => self.symbol(name),
+            Node::Accessor { object, property } => self.accessor(object, property),
+            Node::Negate(inner) => {
+                let inner = self.evaluate_node(inner)?;
+                Ok(Quantity {
+                    value: -inner.value,
+                    ..inner
+                })
+            }
+            Node::Binary { op, lhs, rhs } => {
+                let lhs = self.evaluate_node(lhs)?;
+                let rhs = self.evaluate_node(rhs)?;
+                match op {
+                    BinaryOp::Add => Ok(Quantity {
+                        value: lhs.value + rhs.value,
+                        dimensions: lhs.same_dimensions(rhs, "add")?,
+                    }),
+                    BinaryOp::Subtract => Ok(Quantity {
+                        value: lhs.value - rhs.value,
+                        dimensions: lhs.same_dimensions(rhs, "subtract")?,
+                    }),
+                    BinaryOp::Multiply => Ok(Quantity {
+                        value: lhs.value * rhs.value,
+                        dimensions: lhs.dimensions.combine(rhs.dimensions, |a, b| a + b),
+                    }),
+                    BinaryOp::Divide => Ok(Quantity {
+                        value: lhs.value / rhs.value,
+                        dimensions: lhs.dimensions.combine(rhs.dimensions, |a, b| a - b),
+                    }),
+                    BinaryOp::Power => lhs.pow(rhs),
+                }
+            }
+            Node::Call { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate_node(arg))
+                    .collect::<ChoreoResult<Vec<_>>>()?;
+                call(function, &args)
+            }
+        }
+    }
+}
+
+fn call(function: &str, args: &[Quantity]) -> ChoreoResult<Quantity> {
+    let arity = |count: usize| {
+        if args.len() == count {
+            Ok(())
+        } else {
+            Err(ChoreoError::Expression(format!(
+                "{function} expects {count} argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+    let angle = |q: Quantity| {
+        if q.dimensions.is_dimensionless() || q.dimensions == BaseDimensions::new(0, 0, 0, 1) {
+            Ok(q.value)
+        } else {
+            Err(ChoreoError::Expression(format!(
+                "{function} expects an angle, got {}",
+                q.dimensions
+            )))
+        }
+    };
+    let number = |f: fn(f64) -> f64| -> ChoreoResult<Quantity> {
+        arity(1)?;
+        Ok(Quantity::number(f(args[0].expect_number(function)?)))
+    };
+    match function {
+        "sin" | "cos" | "tan" => {
+            arity(1)?;
+            let value = angle(args[0])?;
+            Ok(Quantity::number(match function {
+                "sin" => value.sin(),
+                "cos" => value.cos(),
+                _ => value.tan(),
+            }))
+        }
+        "asin" => number(f64::asin),
+        "acos" => number(f64::acos),
+        "atan" => number(f64::atan),
+        "exp" => number(f64::exp),
+        "log" => number(f64::ln),
+        "log10" => number(f64::log10),
+        "log2" => number(f64::log2),
+        "floor" => number(f64::floor),
+        "ceil" => number(f64::ceil),
+        "round" => number(f64::round),
+        "atan2" => {
+            arity(2)?;
+            args[0].same_dimensions(args[1], "compare")?;
+            Ok(Quantity::number(args[0].value.atan2(args[1].value)))
+        }
+        "sqrt" => {
+            arity(1)?;
+            args[0].pow(Quantity::number(0.5))
+        }
+        "cbrt" => {
+            arity(1)?;
+            args[0].pow(Quantity::number(1.0 / 3.0))
+        }
+        "pow" => {
+            arity(2)?;
+            args[0].pow(args[1])
+        }
+        "abs" => {
+            arity(1)?;
+            Ok(Quantity {
+                value: args[0].value.abs(),
+                ..args[0]
+            })
+        }
+        "sign" => {
+            arity(1)?;
+            Ok(Quantity::number(if args[0].value == 0.0 {
+                0.0
+            } else {
+                args[0].value.signum()
+            }))
+        }
+        "min" | "max" | "hypot" => {
+            let first = *args.first().ok_or_else(|| {
+                ChoreoError::Expression(format!("{function} expects at least 1 argument"))
+            })?;
+            let mut value = first.value;
+            for arg in &args[1..] {
+                first.same_dimensions(*arg, "compare")?;
+                value = match function {
+                    "min" => value.min(arg.value),
+                    "max" => value.max(arg.value),
+                    _ => value.hypot(arg.value),
+                };
+            }
+            if function == "hypot" {
+                value = value.abs();
+            }
+            Ok(Quantity {
+                value,
+                dimensions: first.dimensions,
+            })
+        }
+        _ => Err(ChoreoError::Expression(format!(
+            "Unknown function {function}"
+        ))),
+    }
+}
+
+/// The message of an expression error, without the error type prefix.
+pub(crate) fn expression_message(error: &ChoreoError) -> String {
+    match error {
+        ChoreoError::Expression(message) => message.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether `name` is a unit or constant that expressions can use without a variable.
+#[must_use]
+pub fn is_builtin_symbol(name: &str) -> bool {
+    units::lookup(name).is_some() || units::constant(name).is_some()
+}
+
+impl Expr {
+    /// Evaluate [`Expr::exp`] in `scope`, ignoring the stored [`Expr::val`].
+    pub fn evaluate(&self, scope: &Scope) -> ChoreoResult<Quantity> {
+        scope.evaluate(&self.exp)
+    }
+}
+
+/// Recompute the [`Expr::val`] of every expression reached by `walk`,
+/// prefixing errors with the JSON path of the failing expression.
+fn recompute(
+    scope: &Scope,
+    walk: impl FnOnce(&mut dyn FnMut(&str, &mut Expr)),
+) -> ChoreoResult<()> {
+    let mut result = Ok(());
+    walk(&mut |path, expr| {
+        if result.is_err() {
+            return;
+        }
+        match expr.evaluate(scope) {
+            Ok(quantity) => expr.val = quantity.value,
+            Err(e) => {
+                result = Err(ChoreoError::Expression(format!(
+                    "{path} ({}): {}",
+                    expr.exp,
+                    expression_message(&e)
+                )));
+            }
+        }
+    });
+    result
+}
+
+impl ProjectFile {
+    /// Re-evaluate every expression in the project, including the variables themselves,
+    /// and overwrite the stored values.
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Expression`] if any expression fails to parse or evaluate.
+    pub fn recompute_expressions(&mut self) -> ChoreoResult<()> {
+        let variables = self.variables.clone();
+        let scope = Scope::new(&variables);
+        recompute(&scope, |f| {
+            self.for_each_expr_mut(|path, _, expr| f(path, expr));
+        })
+    }
+}
+
+impl TrajectoryFile {
+    /// Re-evaluate every expression in the trajectory parameters and events against the
+    /// project `variables`, and overwrite the stored values.
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Expression`] if any expression fails to parse or evaluate.
+    pub fn recompute_expressions(&mut self, variables: &Variables) -> ChoreoResult<()> {
+        let scope = Scope::new(variables);
+        recompute(&scope, |f| {
+            self.for_each_expr_mut(|path, _, expr| f(path, expr));
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::spec::project::{Dimension, PoseVariable, Variable};
+
+    fn eval(exp: &str) -> Quantity {
+        Scope::empty().evaluate(exp).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} was not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn default_project_values() {
+        let mut project = ProjectFile::default();
+        let expected = project.config.snapshot();
+        project.config.mass.val = 0.0;
+        project.config.vmax.val = 0.0;
+        project.recompute_expressions().unwrap();
+        let actual = project.config.snapshot();
+        assert_close(actual.mass, expected.mass);
+        assert_close(actual.vmax, expected.vmax);
+        assert_close(actual.front_left.x, expected.front_left.x);
+        assert_close(actual.back_left.x, expected.back_left.x);
+        assert_close(
+            actual.differential_track_width,
+            expected.differential_track_width,
+        );
+    }
+
+    #[test]
+    fn mathjs_formatting() {
+        let q = eval("6 kg m ^ 2");
+        assert_close(q.value, 6.0);
+        assert_eq!(q.dimensions, BaseDimensions::new(2, 1, 0, 0));
+        let q = eval("10 m / s ^ 2");
+        assert_close(q.value, 10.0);
+        assert_eq!(q.dimensions, BaseDimensions::new(1, 0, -2, 0));
+        let q = eval("1.2 N * m");
+        assert_eq!(q.dimensions, BaseDimensions::new(2, 1, -2, 0));
+        assert_close(eval("3 / 4 m").value, 0.75);
+        assert_close(eval("-2 ^ 2").value, -4.0);
+        assert_close(eval("90 deg").value, std::f64::consts::FRAC_PI_2);
+        assert_close(eval("1e-05 m").value, 1e-5);
+        assert_close(eval("sin(30 deg)").value, 0.5);
+        assert!(Scope::empty().evaluate("1 m + 1 s").is_err());
+        assert!(Scope::empty().evaluate("1 m +").is_err());
+    }
+
+    #[test]
+    fn variables() {
+        let mut expressions = BTreeMap::new();
+        expressions.insert(
+            "width".to_string(),
+            Variable {
+                dimension: Dimension::Length,
+                var: Expr::new("20 in", 0.0),
+            },
+        );
+        expressions.insert(
+            "half".to_string(),
+            Variable {
+                dimension: Dimension::Length,
+                var: Expr::new("width / 2", 0.0),
+            },
+        );
+        expressions.insert(
+            "loop".to_string(),
+            Variable {
+                dimension: Dimension::Number,
+                var: Expr::new("loop + 1", 0.0),
+            },
+        );
+        let mut poses = BTreeMap::new();
+        poses.insert(
+            "score".to_string(),
+            PoseVariable {
+                x: Expr::new("half + 1 m", 0.0),
+                y: Expr::new("2 m", 0.0),
+                heading: Expr::new("180 deg", 0.0),
+            },
+        );
+        let variables = Variables { expressions, poses };
+        let scope = Scope::new(&variables);
+        assert_close(scope.evaluate("half").unwrap().value, 0.254);
+        assert_close(scope.evaluate("score.x").unwrap().value, 1.254);
+        assert_close(
+            scope.evaluate("score.heading").unwrap().value,
+            std::f64::consts::PI,
+        );
+        assert!(scope.evaluate("loop").is_err());
+        assert!(scope.evaluate("score.z").is_err());
+    }
+}
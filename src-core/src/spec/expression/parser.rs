@@ -0,0 +1,397 @@
+use std::ops::Range;
+
+use crate::{ChoreoError, ChoreoResult};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum TokenKind {
+    Number(f64),
+    Ident(String),
+    Operator(char),
+    LeftParen,
+    RightParen,
+    Comma,
+    Dot,
+}
+
+/// A token of an expression, with the byte range it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$' || c == '#'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    is_ident_start(c) || c.is_ascii_digit()
+}
+
+/// Split an expression into tokens.
+pub(super) fn tokenize(input: &str) -> ChoreoResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let next_is_digit = input[start + c.len_utf8()..]
+            .chars()
+            .next()
+            .is_some_and(|n| n.is_ascii_digit());
+        if c.is_ascii_digit() || (c == '.' && next_is_digit) {
+            let mut end = start;
+            let mut seen_exponent = false;
+            while let Some(&(i, n)) = chars.peek() {
+                let prev = input[..i].chars().last();
+                let is_exponent_sign =
+                    (n == '-' || n == '+') && matches!(prev, Some('e' | 'E')) && seen_exponent;
+                let is_exponent = (n == 'e' || n == 'E')
+                    && !seen_exponent
+                    && input[i + 1..]
+                        .chars()
+                        .next()
+                        .is_some_and(|d| d.is_ascii_digit() || d == '-' || d == '+');
+                if n.is_ascii_digit() || n == '.' || is_exponent_sign || is_exponent {
+                    seen_exponent |= is_exponent;
+                    end = i + n.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let text = &input[start..end];
+            let value = text.parse::<f64>().map_err(|_| {
+                ChoreoError::Expression(format!("Invalid number \"{text}\" at position {start}"))
+            })?;
+            tokens.push(Token {
+                kind: TokenKind::Number(value),
+                span: start..end,
+            });
+        } else if is_ident_start(c) {
+            let mut end = start;
+            while let Some(&(i, n)) = chars.peek() {
+                if is_ident_continue(n) {
+                    end = i + n.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token {
+                kind: TokenKind::Ident(input[start..end].to_string()),
+                span: start..end,
+            });
+        } else {
+            let kind = match c {
+                '+' | '-' | '*' | '/' | '^' => TokenKind::Operator(c),
+                '(' => TokenKind::LeftParen,
+                ')' => TokenKind::RightParen,
+                ',' => TokenKind::Comma,
+                '.' => TokenKind::Dot,
+                _ => {
+                    return Err(ChoreoError::Expression(format!(
+                        "Unexpected character '{c}' at position {start}"
+                    )))
+                }
+            };
+            chars.next();
+            tokens.push(Token {
+                kind,
+                span: start..start + c.len_utf8(),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+/// A binary operator in an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// A numeric literal.
+    Number(f64),
+    /// A bare name; a variable, constant or unit.
+    Symbol(String),
+    /// A property of a named object, such as `pose.x`.
+    Accessor { object: String, property: String },
+    /// Unary negation.
+    Negate(Box<Node>),
+    /// A binary operation.
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Node>,
+        rhs: Box<Node>,
+    },
+    /// A function call such as `sin(30 deg)`.
+    Call { function: String, args: Vec<Node> },
+}
+
+impl Node {
+    /// Parse an expression string.
+    ///
+    /// Operator precedence follows `mathjs`, which the Choreo GUI uses to write these
+    /// expressions, including implicit multiplication binding tighter than `*` and `/`
+    /// (so `10 m / s ^ 2` is `(10 m) / (s ^ 2)`).
+    pub fn parse(input: &str) -> ChoreoResult<Node> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+        };
+        let node = parser.additive()?;
+        match parser.peek() {
+            None => Ok(node),
+            Some(token) => Err(parser.unexpected(token)),
+        }
+    }
+
+    /// The names of all symbols and accessor objects referenced by this expression,
+    /// in order of appearance.
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_symbols(&mut out);
+        out
+    }
+
+    fn collect_symbols<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Node::Number(_) => {}
+            Node::Symbol(name) => out.push(name),
+            Node::Accessor { object, .. } => out.push(object),
+            Node::Negate(inner) => inner.collect_symbols(out),
+            Node::Binary { lhs, rhs, .. } => {
+                lhs.collect_symbols(out);
+                rhs.collect_symbols(out);
+            }
+            Node::Call { args, .. } => args.iter().for_each(|a| a.collect_symbols(out)),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_kind(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| &t.kind)
+    }
+
+    fn unexpected(&self, token: &Token) -> ChoreoError {
+        ChoreoError::Expression(format!(
+            "Unexpected token {:?} at position {}",
+            token.kind, token.span.start
+        ))
+    }
+
+    fn end_of_input() -> ChoreoError {
+        ChoreoError::Expression("Unexpected end of expression".to_string())
+    }
+
+    fn expect(&mut self, kind: &TokenKind) -> ChoreoResult<()> {
+        match self.peek() {
+            Some(token) if &token.kind == kind => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => Err(self.unexpected(token)),
+            None => Err(Self::end_of_input()),
+        }
+    }
+
+    fn operator(&self) -> Option<char> {
+        match self.peek_kind(0) {
+            Some(TokenKind::Operator(op)) => Some(*op),
+            _ => None,
+        }
+    }
+
+    fn additive(&mut self) -> ChoreoResult<Node> {
+        let mut node = self.multiplicative()?;
+        while let Some(op @ ('+' | '-')) = self.operator() {
+            self.pos += 1;
+            let rhs = self.multiplicative()?;
+            node = Node::Binary {
+                op: if op == '+' {
+                    BinaryOp::Add
+                } else {
+                    BinaryOp::Subtract
+                },
+                lhs: Box::new(node),
+                rhs: Box::new(rhs),
+            };
+        }
+        Ok(node)
+    }
+
+    fn multiplicative(&mut self) -> ChoreoResult<Node> {
+        let mut node = self.implicit_multiplication()?;
+        while let Some(op @ ('*' | '/')) = self.operator() {
+            self.pos += 1;
+            let rhs = self.implicit_multiplication()?;
+            node = Node::Binary {
+                op: if op == '*' {
+                    BinaryOp::Multiply
+                } else {
+                    BinaryOp::Divide
+                },
+                lhs: Box::new(node),
+                rhs: Box::new(rhs),
+            };
+        }
+        Ok(node)
+    }
+
+    fn starts_implicit_operand(&self, last: &Node) -> bool {
+        match self.peek_kind(0) {
+            Some(TokenKind::Ident(_) | TokenKind::LeftParen) => true,
+            Some(TokenKind::Number(_)) => !matches!(last, Node::Number(_)),
+            _ => false,
+        }
+    }
+
+    fn implicit_multiplication(&mut self) -> ChoreoResult<Node> {
+        let mut node = self.number_division()?;
+        while self.starts_implicit_operand(&node) {
+            let rhs = self.number_division()?;
+            node = Node::Binary {
+                op: BinaryOp::Multiply,
+                lhs: Box::new(node),
+                rhs: Box::new(rhs),
+            };
+        }
+        Ok(node)
+    }
+
+    /// `mathjs` gives `[number] / [number]` precedence over a following implicit
+    /// multiplication, so `3 / 4 mm` is `(3 / 4) mm`.
+    fn number_division(&mut self) -> ChoreoResult<Node> {
+        let mut node = self.unary()?;
+        loop {
+            let is_number = match &node {
+                Node::Number(_) => true,
+                Node::Negate(inner) => matches!(**inner, Node::Number(_)),
+                Node::Binary {
+                    op: BinaryOp::Divide,
+                    lhs,
+                    rhs,
+                } => matches!(**lhs, Node::Number(_)) && matches!(**rhs, Node::Number(_)),
+                _ => false,
+            };
+            let pattern = self.operator() == Some('/')
+                && matches!(self.peek_kind(1), Some(TokenKind::Number(_)))
+                && matches!(
+                    self.peek_kind(2),
+                    Some(TokenKind::Ident(_) | TokenKind::LeftParen)
+                );
+            if !(is_number && pattern) {
+                return Ok(node);
+            }
+            self.pos += 1;
+            let rhs = self.unary()?;
+            node = Node::Binary {
+                op: BinaryOp::Divide,
+                lhs: Box::new(node),
+                rhs: Box::new(rhs),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> ChoreoResult<Node> {
+        match self.operator() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Node::Negate(Box::new(self.unary()?)))
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> ChoreoResult<Node> {
+        let base = self.postfix()?;
+        if self.operator() == Some('^') {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(Node::Binary {
+                op: BinaryOp::Power,
+                lhs: Box::new(base),
+                rhs: Box::new(exponent),
+            });
+        }
+        Ok(base)
+    }
+
+    fn postfix(&mut self) -> ChoreoResult<Node> {
+        let token = self.peek().cloned().ok_or_else(Self::end_of_input)?;
+        self.pos += 1;
+        match token.kind {
+            TokenKind::Number(value) => Ok(Node::Number(value)),
+            TokenKind::LeftParen => {
+                let node = self.additive()?;
+                self.expect(&TokenKind::RightParen)?;
+                Ok(node)
+            }
+            TokenKind::Ident(name) => match self.peek_kind(0) {
+                Some(TokenKind::LeftParen) => {
+                    self.pos += 1;
+                    let mut args = Vec::new();
+                    if self.peek_kind(0) != Some(&TokenKind::RightParen) {
+                        loop {
+                            args.push(self.additive()?);
+                            if self.peek_kind(0) == Some(&TokenKind::Comma) {
+                                self.pos += 1;
+                            } else {
+                                break;
+                            }
+                        }
+                    }
+                    self.expect(&TokenKind::RightParen)?;
+                    Ok(Node::Call {
+                        function: name,
+                        args,
+                    })
+                }
+                Some(TokenKind::Dot) => {
+                    self.pos += 1;
+                    match self.peek().cloned() {
+                        Some(Token {
+                            kind: TokenKind::Ident(property),
+                            ..
+                        }) => {
+                            self.pos += 1;
+                            Ok(Node::Accessor {
+                                object: name,
+                                property,
+                            })
+                        }
+                        Some(token) => Err(self.unexpected(&token)),
+                        None => Err(Self::end_of_input()),
+                    }
+                }
+                _ => Ok(Node::Symbol(name)),
+            },
+            _ => Err(self.unexpected(&token)),
+        }
+    }
+}
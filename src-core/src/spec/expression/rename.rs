@@ -0,0 +1,195 @@
+use crate::{
+    spec::{project::ProjectFile, trajectory::TrajectoryFile, Expr},
+    ChoreoError, ChoreoResult,
+};
+
+use super::{
+    is_builtin_symbol,
+    parser::{tokenize, TokenKind},
+};
+
+/// Rewrite every reference to the variable `old` in `exp` to `new`.
+///
+/// Function names and pose properties (the `x` in `pose.x`) are never references,
+/// even if they happen to share the variable's name.
+/// Returns `None` if `exp` doesn't reference `old`.
+///
+/// # Errors
+/// - [`ChoreoError::Expression`] if `exp` can't be tokenized.
+pub fn rename_symbol(exp: &str, old: &str, new: &str) -> ChoreoResult<Option<String>> {
+    let tokens = tokenize(exp)?;
+    let mut out = String::with_capacity(exp.len());
+    let mut copied = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        let TokenKind::Ident(name) = &token.kind else {
+            continue;
+        };
+        let is_property = i > 0 && tokens[i - 1].kind == TokenKind::Dot;
+        let is_function = tokens
+            .get(i + 1)
+            .is_some_and(|t| t.kind == TokenKind::LeftParen);
+        if name == old && !is_property && !is_function {
+            out.push_str(&exp[copied..token.span.start]);
+            out.push_str(new);
+            copied = token.span.end;
+        }
+    }
+    if copied == 0 {
+        return Ok(None);
+    }
+    out.push_str(&exp[copied..]);
+    Ok(Some(out))
+}
+
+/// Rewrite the expressions reached by `walk`, returning whether any of them changed.
+fn rename_in(
+    old: &str,
+    new: &str,
+    walk: impl FnOnce(&mut dyn FnMut(&str, &mut Expr)),
+) -> ChoreoResult<bool> {
+    let mut result = Ok(false);
+    walk(&mut |path, expr| {
+        let Ok(changed) = &mut result else {
+            return;
+        };
+        match rename_symbol(&expr.exp, old, new) {
+            Ok(Some(exp)) => {
+                expr.exp = exp;
+                *changed = true;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                result = Err(ChoreoError::Expression(format!(
+                    "{path} ({}): {}",
+                    expr.exp,
+                    super::expression_message(&e)
+                )));
+            }
+        }
+    });
+    result
+}
+
+fn is_identifier(name: &str) -> bool {
+    matches!(
+        tokenize(name).as_deref(),
+        Ok([token]) if token.kind == TokenKind::Ident(name.to_string())
+    )
+}
+
+impl ProjectFile {
+    /// Rename the expression or pose variable `old` to `new`,
+    /// rewriting every expression in the project that references it.
+    ///
+    /// Trajectories referencing the variable must be updated separately
+    /// with [`TrajectoryFile::rename_variable`].
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Variable`] if `old` doesn't exist, or `new` is not a valid name
+    ///   or is already taken by a variable, unit or constant.
+    /// - [`ChoreoError::Expression`] if an expression can't be tokenized.
+    pub fn rename_variable(&mut self, old: &str, new: &str) -> ChoreoResult<()> {
+        let variables = &self.variables;
+        if !variables.expressions.contains_key(old) && !variables.poses.contains_key(old) {
+            return Err(ChoreoError::Variable(format!(
+                "No variable named \"{old}\""
+            )));
+        }
+        if !is_identifier(new) {
+            return Err(ChoreoError::Variable(format!(
+                "\"{new}\" is not a valid variable name"
+            )));
+        }
+        if variables.expressions.contains_key(new)
+            || variables.poses.contains_key(new)
+            || is_builtin_symbol(new)
+        {
+            return Err(ChoreoError::Variable(format!(
+                "The name \"{new}\" is already in use"
+            )));
+        }
+        if let Some(variable) = self.variables.expressions.remove(old) {
+            self.variables.expressions.insert(new.to_string(), variable);
+        }
+        if let Some(pose) = self.variables.poses.remove(old) {
+            self.variables.poses.insert(new.to_string(), pose);
+        }
+        rename_in(old, new, |f| {
+            self.for_each_expr_mut(|path, _, expr| f(path, expr));
+        })
+        .map(|_| ())
+    }
+}
+
+impl TrajectoryFile {
+    /// Rewrite every expression and pose link in the trajectory that references
+    /// the variable `old` to reference `new` instead.
+    ///
+    /// Only the expression strings change, so the trajectory stays up to date.
+    /// Returns `true` if anything referenced `old`.
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Expression`] if an expression can't be tokenized.
+    pub fn rename_variable(&mut self, old: &str, new: &str) -> ChoreoResult<bool> {
+        let mut changed = rename_in(old, new, |f| {
+            self.for_each_expr_mut(|path, _, expr| f(path, expr));
+        })?;
+        for waypoint in &mut self.params.waypoints {
+            if waypoint.pose.as_deref() == Some(old) {
+                waypoint.pose = Some(new.to_string());
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::project::{PoseVariable, Variable};
+
+    #[test]
+    fn symbols() {
+        let rename = |exp| rename_symbol(exp, "a", "speed").unwrap();
+        assert_eq!(rename("2 a"), Some("2 speed".to_string()));
+        assert_eq!(rename("a*a+ab"), Some("speed*speed+ab".to_string()));
+        assert_eq!(rename("a.x + pose.a"), Some("speed.x + pose.a".to_string()));
+        assert_eq!(rename("a(3) + b"), None);
+        assert_eq!(rename("1 m"), None);
+    }
+
+    #[test]
+    fn project() {
+        let mut project = ProjectFile::default();
+        project.variables.expressions.insert(
+            "len".to_string(),
+            Variable {
+                dimension: crate::spec::project::Dimension::Length,
+                var: Expr::new("2 m", 2.0),
+            },
+        );
+        project.variables.poses.insert(
+            "score".to_string(),
+            PoseVariable {
+                x: Expr::new("len", 2.0),
+                y: Expr::new("len / 2", 1.0),
+                heading: Expr::new("0 deg", 0.0),
+            },
+        );
+        project.config.radius = Expr::new("len / 40", 0.05);
+
+        project.rename_variable("len", "length").unwrap();
+        assert!(project.variables.expressions.contains_key("length"));
+        assert!(!project.variables.expressions.contains_key("len"));
+        assert_eq!(project.variables.poses["score"].y.exp, "length / 2");
+        assert_eq!(project.config.radius.exp, "length / 40");
+
+        assert!(project.rename_variable("len", "other").is_err());
+        assert!(project.rename_variable("score", "length").is_err());
+        assert!(project.rename_variable("score", "m").is_err());
+        assert!(project.rename_variable("score", "two words").is_err());
+        project.rename_variable("score", "target").unwrap();
+        assert!(project.variables.poses.contains_key("target"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::f64::consts::{PI, TAU};
+
+use super::{BaseDimensions, Quantity};
+
+const LENGTH: BaseDimensions = BaseDimensions::new(1, 0, 0, 0);
+const MASS: BaseDimensions = BaseDimensions::new(0, 1, 0, 0);
+const TIME: BaseDimensions = BaseDimensions::new(0, 0, 1, 0);
+const ANGLE: BaseDimensions = BaseDimensions::new(0, 0, 0, 1);
+const FREQUENCY: BaseDimensions = BaseDimensions::new(0, 0, -1, 0);
+const ANGULAR_VELOCITY: BaseDimensions = BaseDimensions::new(0, 0, -1, 1);
+const FORCE: BaseDimensions = BaseDimensions::new(1, 1, -2, 0);
+const ENERGY: BaseDimensions = BaseDimensions::new(2, 1, -2, 0);
+
+const INCH: f64 = 0.0254;
+const POUND_MASS: f64 = 0.453_592_37;
+const STANDARD_GRAVITY: f64 = 9.806_65;
+
+/// The units understood by the expression evaluator, with their value in SI base units.
+///
+/// This mirrors the subset of the `mathjs` unit vocabulary that the Choreo GUI writes
+/// into `.chor` and `.traj` files, plus the custom `RPM` unit the GUI registers.
+const UNITS: &[(&str, f64, BaseDimensions)] = &[
+    // Length
+    ("m", 1.0, LENGTH),
+    ("meter", 1.0, LENGTH),
+    ("meters", 1.0, LENGTH),
+    ("metre", 1.0, LENGTH),
+    ("metres", 1.0, LENGTH),
+    ("km", 1000.0, LENGTH),
+    ("cm", 0.01, LENGTH),
+    ("mm", 0.001, LENGTH),
+    ("in", INCH, LENGTH),
+    ("inch", INCH, LENGTH),
+    ("inches", INCH, LENGTH),
+    ("ft", 12.0 * INCH, LENGTH),
+    ("foot", 12.0 * INCH, LENGTH),
+    ("feet", 12.0 * INCH, LENGTH),
+    ("yd", 36.0 * INCH, LENGTH),
+    ("yard", 36.0 * INCH, LENGTH),
+    ("yards", 36.0 * INCH, LENGTH),
+    ("mi", 63_360.0 * INCH, LENGTH),
+    ("mile", 63_360.0 * INCH, LENGTH),
+    ("miles", 63_360.0 * INCH, LENGTH),
+    // Mass
+    ("kg", 1.0, MASS),
+    ("g", 0.001, MASS),
+    ("gram", 0.001, MASS),
+    ("grams", 0.001, MASS),
+    ("lb", POUND_MASS, MASS),
+    ("lbs", POUND_MASS, MASS),
+    ("lbm", POUND_MASS, MASS),
+    ("pound", POUND_MASS, MASS),
+    ("pounds", POUND_MASS, MASS),
+    ("oz", POUND_MASS / 16.0, MASS),
+    ("ounce", POUND_MASS / 16.0, MASS),
+    ("ounces", POUND_MASS / 16.0, MASS),
+    // Time
+    ("s", 1.0, TIME),
+    ("sec", 1.0, TIME),
+    ("secs", 1.0, TIME),
+    ("second", 1.0, TIME),
+    ("seconds", 1.0, TIME),
+    ("ms", 0.001, TIME),
+    ("min", 60.0, TIME),
+    ("mins", 60.0, TIME),
+    ("minute", 60.0, TIME),
+    ("minutes", 60.0, TIME),
+    ("h", 3600.0, TIME),
+    ("hr", 3600.0, TIME),
+    ("hour", 3600.0, TIME),
+    ("hours", 3600.0, TIME),
+    ("Hz", 1.0, FREQUENCY),
+    ("hertz", 1.0, FREQUENCY),
+    // Angle
+    ("rad", 1.0, ANGLE),
+    ("radian", 1.0, ANGLE),
+    ("radians", 1.0, ANGLE),
+    ("deg", PI / 180.0, ANGLE),
+    ("degree", PI / 180.0, ANGLE),
+    ("degrees", PI / 180.0, ANGLE),
+    ("grad", PI / 200.0, ANGLE),
+    ("gradian", PI / 200.0, ANGLE),
+    ("cycle", TAU, ANGLE),
+    ("cycles", TAU, ANGLE),
+    ("RPM", TAU / 60.0, ANGULAR_VELOCITY),
+    ("rpm", TAU / 60.0, ANGULAR_VELOCITY),
+    // Force and energy
+    ("N", 1.0, FORCE),
+    ("newton", 1.0, FORCE),
+    ("newtons", 1.0, FORCE),
+    ("kN", 1000.0, FORCE),
+    ("lbf", POUND_MASS * STANDARD_GRAVITY, FORCE),
+    ("J", 1.0, ENERGY),
+    ("joule", 1.0, ENERGY),
+    ("joules", 1.0, ENERGY),
+];
+
+/// Look up a unit by the name used in an expression.
+pub(super) fn lookup(name: &str) -> Option<Quantity> {
+    UNITS
+        .iter()
+        .find(|(unit, _, _)| *unit == name)
+        .map(|&(_, value, dimensions)| Quantity { value, dimensions })
+}
+
+/// Look up a named constant such as `pi`.
+pub(super) fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "PI" => Some(PI),
+        "tau" => Some(TAU),
+        "e" | "E" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
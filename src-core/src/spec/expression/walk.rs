@@ -0,0 +1,146 @@
+use crate::spec::{
+    project::{Dimension, ProjectFile},
+    trajectory::{ConstraintData, PplibCommand, TrajectoryFile},
+    Expr,
+};
+
+/// Generates a shared and a mutable variant of each expression walker,
+/// so the list of expressions and their dimensions only lives in one place.
+macro_rules! expr_walkers {
+    ($walker:ident, $command_fn:ident $(, $mutability:tt)?) => {
+        fn $command_fn<F: FnMut(&str, Dimension, &$($mutability)? Expr)>(
+            command: &$($mutability)? PplibCommand,
+            path: &str,
+            f: &mut F,
+        ) {
+            match command {
+                PplibCommand::Named { .. } => {}
+                PplibCommand::Wait { wait_time } => {
+                    f(&format!("{path}.data.waitTime"), Dimension::Time, wait_time);
+                }
+                PplibCommand::Sequential { commands }
+                | PplibCommand::Parallel { commands }
+                | PplibCommand::Race { commands }
+                | PplibCommand::Deadline { commands } => {
+                    for (i, command) in commands.into_iter().enumerate() {
+                        $command_fn(command, &format!("{path}.data.commands.{i}"), f);
+                    }
+                }
+            }
+        }
+
+        impl ProjectFile {
+            /// Call `f` with the JSON path and expected dimension of every expression
+            /// in the project, including variables and the robot config.
+            pub fn $walker<F: FnMut(&str, Dimension, &$($mutability)? Expr)>(
+                &$($mutability)? self,
+                mut f: F,
+            ) {
+                for (name, variable) in &$($mutability)? self.variables.expressions {
+                    let dimension = variable.dimension;
+                    f(
+                        &format!("variables.expressions.{name}.var"),
+                        dimension,
+                        &$($mutability)? variable.var,
+                    );
+                }
+                for (name, pose) in &$($mutability)? self.variables.poses {
+                    f(&format!("variables.poses.{name}.x"), Dimension::Length, &$($mutability)? pose.x);
+                    f(&format!("variables.poses.{name}.y"), Dimension::Length, &$($mutability)? pose.y);
+                    f(
+                        &format!("variables.poses.{name}.heading"),
+                        Dimension::Angle,
+                        &$($mutability)? pose.heading,
+                    );
+                }
+                let config = &$($mutability)? self.config;
+                for (path, dimension, expr) in [
+                    ("config.frontLeft.x", Dimension::Length, &$($mutability)? config.front_left.x),
+                    ("config.frontLeft.y", Dimension::Length, &$($mutability)? config.front_left.y),
+                    ("config.backLeft.x", Dimension::Length, &$($mutability)? config.back_left.x),
+                    ("config.backLeft.y", Dimension::Length, &$($mutability)? config.back_left.y),
+                    ("config.mass", Dimension::Mass, &$($mutability)? config.mass),
+                    ("config.inertia", Dimension::MoI, &$($mutability)? config.inertia),
+                    ("config.gearing", Dimension::Number, &$($mutability)? config.gearing),
+                    ("config.radius", Dimension::Length, &$($mutability)? config.radius),
+                    ("config.vmax", Dimension::AngVel, &$($mutability)? config.vmax),
+                    ("config.tmax", Dimension::Torque, &$($mutability)? config.tmax),
+                    ("config.cof", Dimension::Number, &$($mutability)? config.cof),
+                    ("config.bumper.front", Dimension::Length, &$($mutability)? config.bumper.front),
+                    ("config.bumper.side", Dimension::Length, &$($mutability)? config.bumper.side),
+                    ("config.bumper.back", Dimension::Length, &$($mutability)? config.bumper.back),
+                    (
+                        "config.differentialTrackWidth",
+                        Dimension::Length,
+                        &$($mutability)? config.differential_track_width,
+                    ),
+                ] {
+                    f(path, dimension, expr);
+                }
+            }
+        }
+
+        impl TrajectoryFile {
+            /// Call `f` with the JSON path and expected dimension of every expression
+            /// in the trajectory parameters and event markers.
+            pub fn $walker<F: FnMut(&str, Dimension, &$($mutability)? Expr)>(
+                &$($mutability)? self,
+                mut f: F,
+            ) {
+                let params = &$($mutability)? self.params;
+                for (i, waypoint) in (&$($mutability)? params.waypoints).into_iter().enumerate() {
+                    let path = format!("params.waypoints.{i}");
+                    f(&format!("{path}.x"), Dimension::Length, &$($mutability)? waypoint.x);
+                    f(&format!("{path}.y"), Dimension::Length, &$($mutability)? waypoint.y);
+                    f(&format!("{path}.heading"), Dimension::Angle, &$($mutability)? waypoint.heading);
+                }
+                for (i, constraint) in (&$($mutability)? params.constraints).into_iter().enumerate() {
+                    let path = format!("params.constraints.{i}.data.props");
+                    let fields = match &$($mutability)? constraint.data {
+                        ConstraintData::MaxVelocity { max } => vec![("max", Dimension::LinVel, max)],
+                        ConstraintData::MaxAcceleration { max } => vec![("max", Dimension::LinAcc, max)],
+                        ConstraintData::MaxAngularVelocity { max } => vec![("max", Dimension::AngVel, max)],
+                        ConstraintData::PointAt { x, y, tolerance, .. } => vec![
+                            ("x", Dimension::Length, x),
+                            ("y", Dimension::Length, y),
+                            ("tolerance", Dimension::Angle, tolerance),
+                        ],
+                        ConstraintData::StopPoint {} => vec![],
+                        ConstraintData::KeepInCircle { x, y, r }
+                        | ConstraintData::KeepOutCircle { x, y, r } => vec![
+                            ("x", Dimension::Length, x),
+                            ("y", Dimension::Length, y),
+                            ("r", Dimension::Length, r),
+                        ],
+                        ConstraintData::KeepInRectangle { x, y, w, h } => vec![
+                            ("x", Dimension::Length, x),
+                            ("y", Dimension::Length, y),
+                            ("w", Dimension::Length, w),
+                            ("h", Dimension::Length, h),
+                        ],
+                        ConstraintData::KeepInLane { tolerance } => {
+                            vec![("tolerance", Dimension::Length, tolerance)]
+                        }
+                    };
+                    for (field, dimension, expr) in fields {
+                        f(&format!("{path}.{field}"), dimension, expr);
+                    }
+                }
+                f("params.targetDt", Dimension::Time, &$($mutability)? params.target_dt);
+                for (i, event) in (&$($mutability)? self.events).into_iter().enumerate() {
+                    f(
+                        &format!("events.{i}.from.offset"),
+                        Dimension::Time,
+                        &$($mutability)? event.from.offset,
+                    );
+                    if let Some(command) = &$($mutability)? event.event {
+                        $command_fn(command, &format!("events.{i}.event"), &mut f);
+                    }
+                }
+            }
+        }
+    };
+}
+
+expr_walkers!(for_each_expr, walk_command);
+expr_walkers!(for_each_expr_mut, walk_command_mut, mut);
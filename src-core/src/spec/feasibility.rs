@@ -0,0 +1,411 @@
+//! Checks that a generated trajectory respects the limits of the robot
+//! and the constraints it was generated with.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    project::RobotConfig,
+    trajectory::{Constraint, ConstraintData, ConstraintScope, Parameters, Sample, TrajectoryFile},
+    SnapshottableType,
+};
+use crate::generation::angle_modulus;
+
+/// The acceleration due to gravity used by the generator for wheel friction.
+///
+/// Units: meters / second²
+const GRAVITY: f64 = 9.8;
+
+/// How far past a limit a sample can be before [`check`] should report it.
+///
+/// Samples are written rounded to 1e-5, which can put an exact value up to 5e-6
+/// past its limit, so this is no smaller than that.
+pub const DEFAULT_TOLERANCE: f64 = 1e-5;
+
+/// A limit that a sample can break.
+///
+/// Modules are in the order of [`RobotConfig::module_translations`] for swerve,
+/// and left then right for differential drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Limit {
+    /// The wheel of a module is faster than the motor allows.
+    WheelVelocity { module: usize },
+    /// The force on a module needs more torque than the motor has.
+    WheelTorque { module: usize },
+    /// The force on a module is more than the wheel's friction can provide.
+    Friction { module: usize },
+    /// The constraint at this index in the parameters is not met.
+    Constraint { index: usize },
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::WheelVelocity { module } => write!(f, "wheel velocity of module {module}"),
+            Limit::WheelTorque { module } => write!(f, "wheel torque of module {module}"),
+            Limit::Friction { module } => write!(f, "friction of module {module}"),
+            Limit::Constraint { index } => write!(f, "constraint {index}"),
+        }
+    }
+}
+
+/// A sample that breaks a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Violation {
+    /// The index of the sample.
+    pub sample: usize,
+    /// The timestamp of the sample in seconds.
+    pub t: f64,
+    /// The limit that was broken.
+    pub limit: Limit,
+    /// The value at the sample, in SI units.
+    pub value: f64,
+    /// The bound on the value.
+    pub bound: f64,
+    /// How far within the bound the value is, so always negative for a violation.
+    pub margin: f64,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sample {} at {} s breaks the {}: {} against a bound of {} (margin {})",
+            self.sample, self.t, self.limit, self.value, self.bound, self.margin
+        )
+    }
+}
+
+/// A value at a sample and the bound it must stay on the right side of.
+struct Check {
+    value: f64,
+    bound: f64,
+    /// Whether the bound is a maximum rather than a minimum.
+    max: bool,
+}
+
+impl Check {
+    const fn at_most(value: f64, bound: f64) -> Self {
+        Self {
+            value,
+            bound,
+            max: true,
+        }
+    }
+
+    fn margin(&self) -> f64 {
+        if self.max {
+            self.bound - self.value
+        } else {
+            self.value - self.bound
+        }
+    }
+}
+
+/// The speed, acceleration and angular velocity of the robot.
+fn motion(sample: &Sample) -> (f64, f64, f64) {
+    match *sample {
+        Sample::Swerve {
+            vx,
+            vy,
+            omega,
+            ax,
+            ay,
+            ..
+        } => (vx.hypot(vy), ax.hypot(ay), omega.abs()),
+        Sample::DifferentialDrive {
+            vl,
+            vr,
+            omega,
+            al,
+            ar,
+            ..
+        } => (
+            ((vl + vr) / 2.0).abs(),
+            ((al + ar) / 2.0).abs(),
+            omega.abs(),
+        ),
+    }
+}
+
+/// The distance from `point` to the segment from `start` to `end`.
+fn segment_distance(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = dx * dx + dy * dy;
+    let along = if length == 0.0 {
+        0.0
+    } else {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length).clamp(0.0, 1.0)
+    };
+    (point.0 - start.0 - along * dx).hypot(point.1 - start.1 - along * dy)
+}
+
+/// The limits of the drivetrain at `sample`.
+fn drivetrain_checks(sample: &Sample, config: &RobotConfig<f64>) -> Vec<(Limit, Check)> {
+    let max_speed = config.wheel_max_velocity() * config.radius;
+    let max_torque = config.wheel_max_torque();
+    let max_friction = config.cof * config.mass * GRAVITY;
+    // The speed of and force on each wheel
+    let wheels = match sample {
+        Sample::Swerve { fx, fy, .. } => sample
+            .module_states(config)
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state.speed, fx[i].hypot(fy[i])))
+            .collect(),
+        Sample::DifferentialDrive { vl, vr, fl, fr, .. } => {
+            vec![(vl.abs(), fl.abs()), (vr.abs(), fr.abs())]
+        }
+    };
+    wheels
+        .into_iter()
+        .enumerate()
+        .flat_map(|(module, (speed, force))| {
+            [
+                (
+                    Limit::WheelVelocity { module },
+                    Check::at_most(speed, max_speed),
+                ),
+                (
+                    Limit::WheelTorque { module },
+                    Check::at_most(force * config.radius, max_torque),
+                ),
+                (
+                    Limit::Friction { module },
+                    Check::at_most(force, max_friction),
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// How well `sample` meets `data`, given the waypoints at either end of the
+/// constraint's segment for lanes.
+fn constraint_check(
+    data: &ConstraintData<f64>,
+    sample: &Sample,
+    lane: Option<((f64, f64), (f64, f64))>,
+    config: &RobotConfig<f64>,
+) -> Option<Check> {
+    let pose = sample.pose();
+    let (speed, acceleration, omega) = motion(sample);
+    let corners = config.bumper.corners(pose.x, pose.y, pose.heading);
+    Some(match *data {
+        ConstraintData::MaxVelocity { max } => Check::at_most(speed, max),
+        ConstraintData::MaxAcceleration { max } => Check::at_most(acceleration, max),
+        ConstraintData::MaxAngularVelocity { max } => Check::at_most(omega, max),
+        ConstraintData::StopPoint {} => Check::at_most(speed.max(omega), 0.0),
+        ConstraintData::PointAt {
+            x,
+            y,
+            tolerance,
+            flip,
+        } => {
+            let facing =
+                (y - pose.y).atan2(x - pose.x) + if flip { std::f64::consts::PI } else { 0.0 };
+            Check::at_most(angle_modulus(pose.heading - facing).abs(), tolerance)
+        }
+        ConstraintData::KeepInCircle { x, y, r } => Check::at_most(
+            corners
+                .iter()
+                .map(|(px, py)| (px - x).hypot(py - y))
+                .fold(0.0, f64::max),
+            r,
+        ),
+        ConstraintData::KeepInRectangle { x, y, w, h } => Check::at_most(
+            // How far the furthest corner is outside the rectangle, negative if inside
+            corners
+                .iter()
+                .map(|(px, py)| (x - px).max(px - x - w).max(y - py).max(py - y - h))
+                .fold(f64::NEG_INFINITY, f64::max),
+            0.0,
+        ),
+        ConstraintData::KeepInLane { tolerance } => {
+            let (start, end) = lane?;
+            let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+            let length = dx.hypot(dy);
+            if length == 0.0 {
+                return None;
+            }
+            Check::at_most(
+                (dx * (pose.y - start.1) - dy * (pose.x - start.0)).abs() / length,
+                tolerance,
+            )
+        }
+        ConstraintData::KeepOutCircle { x, y, r } => Check {
+            value: (0..corners.len())
+                .map(|i| segment_distance((x, y), corners[i], corners[(i + 1) % corners.len()]))
+                .fold(f64::INFINITY, f64::min),
+            bound: r,
+            max: false,
+        },
+    })
+}
+
+/// The samples a constraint applies to, as a range of sample indices,
+/// or `None` if the generator would ignore it.
+fn constraint_samples(
+    constraint: &Constraint<f64>,
+    waypoint_samples: &[usize],
+) -> Option<(usize, usize)> {
+    let count = waypoint_samples.len();
+    let from = constraint.from.get_idx(count)?;
+    let to = match constraint.to {
+        Some(id) => Some(id.get_idx(count)?).filter(|to| *to != from),
+        None => None,
+    };
+    match (constraint.data.scope(), to) {
+        (ConstraintScope::Segment, None) | (ConstraintScope::Waypoint, Some(_)) => None,
+        (_, Some(to)) => Some((
+            waypoint_samples[from.min(to)],
+            waypoint_samples[from.max(to)],
+        )),
+        (_, None) => Some((waypoint_samples[from], waypoint_samples[from])),
+    }
+}
+
+/// Check every sample of `trajectory` against the limits of `config` and the enabled
+/// constraints it was generated with, reporting the limits broken by more than
+/// `tolerance`.
+///
+/// The constraints come from the snapshot of the parameters at the last generation,
+/// or the current parameters if there is none. Region constraints are checked
+/// against the bumpers, and lanes against the center of the robot, like the generator.
+/// The violations are ordered by sample, then drivetrain limits before constraints.
+pub fn check<T: SnapshottableType>(
+    trajectory: &TrajectoryFile,
+    config: &RobotConfig<T>,
+    tolerance: f64,
+) -> Vec<Violation> {
+    let config = config.snapshot();
+    let params: Parameters<f64> = trajectory
+        .snapshot
+        .clone()
+        .unwrap_or_else(|| trajectory.params.snapshot());
+    let samples = &trajectory.trajectory.samples;
+    // The sample closest to each waypoint
+    let waypoint_samples = trajectory
+        .trajectory
+        .waypoints
+        .iter()
+        .map(|t| {
+            let next = samples.partition_point(|s| s.t() < *t);
+            match (next.checked_sub(1), samples.get(next)) {
+                (Some(previous), Some(sample)) if t - samples[previous].t() < sample.t() - t => {
+                    previous
+                }
+                (Some(previous), None) => previous,
+                _ => next,
+            }
+        })
+        .collect::<Vec<_>>();
+    let constraints = params
+        .constraints
+        .iter()
+        .enumerate()
+        .filter(|(_, constraint)| constraint.enabled)
+        .filter_map(|(index, constraint)| {
+            let range = constraint_samples(constraint, &waypoint_samples)?;
+            let lane = constraint
+                .to
+                .and_then(|to| {
+                    let from = constraint.from.get_idx(params.waypoints.len())?;
+                    let to = to.get_idx(params.waypoints.len())?;
+                    Some((&params.waypoints[from], &params.waypoints[to]))
+                })
+                .map(|(from, to)| ((from.x, from.y), (to.x, to.y)));
+            Some((index, constraint, range, lane))
+        })
+        .collect::<Vec<_>>();
+
+    let mut violations = Vec::new();
+    for (i, sample) in samples.iter().enumerate() {
+        let mut checks = drivetrain_checks(sample, &config);
+        checks.extend(
+            constraints
+                .iter()
+                .filter(|(_, _, (from, to), _)| (*from..=*to).contains(&i))
+                .filter_map(|(index, constraint, _, lane)| {
+                    constraint_check(&constraint.data, sample, *lane, &config)
+                        .map(|check| (Limit::Constraint { index: *index }, check))
+                }),
+        );
+        violations.extend(
+            checks
+                .into_iter()
+                .filter(|(_, check)| check.margin() < -tolerance)
+                .map(|(limit, check)| Violation {
+                    sample: i,
+                    t: sample.t(),
+                    limit,
+                    value: check.value,
+                    bound: check.bound,
+                    margin: check.margin(),
+                }),
+        );
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{
+        fixtures::{swerve_project, swerve_trajectory},
+        trajectory::WaypointID,
+    };
+
+    #[test]
+    fn feasible() {
+        let (project, trajectory) = (swerve_project(), swerve_trajectory());
+        assert_eq!(
+            check(&trajectory, &project.config, DEFAULT_TOLERANCE),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn violations() {
+        let (project, mut trajectory) = (swerve_project(), swerve_trajectory());
+        let Sample::Swerve { vx, fx, .. } = &mut trajectory.trajectory.samples[3] else {
+            panic!("expected a swerve sample");
+        };
+        *vx = 100.0;
+        fx[2] = 1e6;
+        trajectory.snapshot = None;
+        trajectory.params.constraints = vec![Constraint {
+            from: WaypointID::First,
+            to: Some(WaypointID::Last),
+            data: ConstraintData::MaxVelocity {
+                max: crate::spec::Expr::new("1 m / s", 1.0),
+            },
+            enabled: true,
+        }];
+        let violations = check(&trajectory, &project.config, 0.0);
+        let at_sample = violations
+            .iter()
+            .filter(|v| v.sample == 3)
+            .map(|v| v.limit)
+            .collect::<Vec<_>>();
+        assert!(at_sample.contains(&Limit::WheelVelocity { module: 0 }));
+        assert!(at_sample.contains(&Limit::WheelTorque { module: 2 }));
+        assert!(at_sample.contains(&Limit::Friction { module: 2 }));
+        assert!(!at_sample.contains(&Limit::Friction { module: 1 }));
+        assert!(at_sample.contains(&Limit::Constraint { index: 0 }));
+        let constraint = violations
+            .iter()
+            .find(|v| v.sample == 3 && v.limit == Limit::Constraint { index: 0 })
+            .unwrap();
+        assert_eq!(constraint.bound, 1.0);
+        assert!(constraint.margin < -90.0);
+
+        trajectory.params.constraints[0].enabled = false;
+        assert!(check(&trajectory, &project.config, 0.0)
+            .iter()
+            .all(|v| !matches!(v.limit, Limit::Constraint { .. })));
+    }
+}
@@ -0,0 +1,3 @@
+// Auto-generated by update_field_dimensions.py
+pub const FIELD_LENGTH: f64 = 17.548;
+pub const FIELD_WIDTH: f64 = 8.052;
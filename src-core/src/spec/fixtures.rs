@@ -0,0 +1,31 @@
+//! The swerve fixtures in `test-jsons`, shared by the tests.
+
+use std::{fs, path::PathBuf};
+
+use super::{
+    project::{ProjectFile, RobotConfig},
+    trajectory::TrajectoryFile,
+};
+
+/// Get the contents of a current version testing json.
+/// Panics if the file does not exist.
+fn contents(path: &str) -> String {
+    let file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("test-jsons")
+        .join(path);
+    fs::read_to_string(&file).unwrap_or_else(|e| panic!("Failed to read {}: {e}", file.display()))
+}
+
+pub fn swerve_project() -> ProjectFile {
+    ProjectFile::from_content(&contents("project/1/swerve.chor")).unwrap()
+}
+
+pub fn swerve_config() -> RobotConfig<f64> {
+    swerve_project().config.snapshot()
+}
+
+pub fn swerve_trajectory() -> TrajectoryFile {
+    TrajectoryFile::from_content(&contents("trajectory/1/swerve.traj")).unwrap()
+}
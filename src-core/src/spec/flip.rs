@@ -0,0 +1,418 @@
+//! Flipping trajectories and their parameters to the red alliance side of the field.
+//!
+//! Choreo trajectories are always drawn from the blue alliance's perspective. Depending on
+//! the year, the red alliance's side of the field is either a mirror image of the blue side
+//! or the blue side rotated half a turn around the center of the field.
+//! This is the same scheme as ChoreoLib's `ChoreoAllianceFlipUtil`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::generation::angle_modulus;
+
+use super::{
+    field_dimensions::{FIELD_LENGTH, FIELD_WIDTH},
+    trajectory::{
+        Constraint, ConstraintData, Parameters, Sample, Trajectory, TrajectoryFile, Waypoint,
+    },
+    SnapshottableType,
+};
+
+/// How the red alliance's side of the field relates to the blue alliance's side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Flipper {
+    /// X becomes `length - x`, y is unchanged and heading becomes `PI - heading`.
+    Mirrored,
+    /// X becomes `length - x`, y becomes `width - y` and heading becomes `PI + heading`.
+    RotateAround,
+}
+
+/// The symmetry and dimensions of a year's field.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+    /// How the field is flipped.
+    pub flipper: Flipper,
+    /// The length of the field along the x axis.
+    ///
+    /// Units: meters
+    pub length: f64,
+    /// The width of the field along the y axis.
+    ///
+    /// Units: meters
+    pub width: f64,
+}
+
+impl Default for Field {
+    /// The current year's field, with the dimensions `update_field_dimensions.py` sets.
+    fn default() -> Self {
+        Self {
+            flipper: Flipper::RotateAround,
+            length: FIELD_LENGTH,
+            width: FIELD_WIDTH,
+        }
+    }
+}
+
+impl Field {
+    /// The field for a competition year, or `None` if the year isn't supported.
+    #[must_use]
+    pub fn for_year(year: u32) -> Option<Self> {
+        let field = |flipper| Self {
+            flipper,
+            length: 16.5811,
+            width: 8.19912,
+        };
+        match year {
+            2020..=2022 => Some(field(Flipper::RotateAround)),
+            2023 | 2024 => Some(field(Flipper::Mirrored)),
+            2025 => Some(Self::default()),
+            _ => None,
+        }
+    }
+
+    /// Flip an x coordinate.
+    #[must_use]
+    pub fn flip_x(&self, x: f64) -> f64 {
+        self.length - x
+    }
+
+    /// Flip a y coordinate.
+    #[must_use]
+    pub fn flip_y(&self, y: f64) -> f64 {
+        match self.flipper {
+            Flipper::Mirrored => y,
+            Flipper::RotateAround => self.width - y,
+        }
+    }
+
+    /// Flip a heading, wrapped to `[-PI, PI]`.
+    #[must_use]
+    pub fn flip_heading(&self, heading: f64) -> f64 {
+        use std::f64::consts::PI;
+        angle_modulus(match self.flipper {
+            Flipper::Mirrored => PI - heading,
+            Flipper::RotateAround => PI + heading,
+        })
+    }
+}
+
+fn flip_value<T: SnapshottableType>(value: &T, unit: &'static str, flip: impl Fn(f64) -> f64) -> T {
+    T::fill_in_value(flip(value.snapshot()), unit)
+}
+
+impl Sample {
+    /// The sample as seen from the other alliance's side of `field`.
+    ///
+    /// Swerve module forces are in the order front left, back left, back right, front right,
+    /// so mirroring also swaps the left and right modules.
+    #[must_use]
+    pub fn flipped(&self, field: &Field) -> Sample {
+        let mirrored = field.flipper == Flipper::Mirrored;
+        match *self {
+            Sample::Swerve {
+                t,
+                x,
+                y,
+                heading,
+                vx,
+                vy,
+                omega,
+                ax,
+                ay,
+                alpha,
+                fx,
+                fy,
+            } => {
+                let y_sign = if mirrored { 1.0 } else { -1.0 };
+                let module = |i: usize| if mirrored { 3 - i } else { i };
+                Sample::Swerve {
+                    t,
+                    x: field.flip_x(x),
+                    y: field.flip_y(y),
+                    heading: field.flip_heading(heading),
+                    vx: -vx,
+                    vy: y_sign * vy,
+                    omega: -y_sign * omega,
+                    ax: -ax,
+                    ay: y_sign * ay,
+                    alpha: -y_sign * alpha,
+                    fx: std::array::from_fn(|i| -fx[module(i)]),
+                    fy: std::array::from_fn(|i| y_sign * fy[module(i)]),
+                }
+            }
+            Sample::DifferentialDrive {
+                t,
+                x,
+                y,
+                heading,
+                vl,
+                vr,
+                omega,
+                al,
+                ar,
+                fl,
+                fr,
+            } => {
+                let x = field.flip_x(x);
+                let y = field.flip_y(y);
+                let heading = field.flip_heading(heading);
+                if mirrored {
+                    Sample::DifferentialDrive {
+                        t,
+                        x,
+                        y,
+                        heading,
+                        vl: vr,
+                        vr: vl,
+                        omega: -omega,
+                        al: ar,
+                        ar: al,
+                        fl: fr,
+                        fr: fl,
+                    }
+                } else {
+                    Sample::DifferentialDrive {
+                        t,
+                        x,
+                        y,
+                        heading,
+                        vl,
+                        vr,
+                        omega,
+                        al,
+                        ar,
+                        fl,
+                        fr,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Trajectory {
+    /// The trajectory as seen from the other alliance's side of `field`.
+    #[must_use]
+    pub fn flipped(&self, field: &Field) -> Trajectory {
+        Trajectory {
+            samples: self.samples.iter().map(|s| s.flipped(field)).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl<T: SnapshottableType> Waypoint<T> {
+    /// The waypoint as seen from the other alliance's side of `field`.
+    ///
+    /// Expressions are replaced by their flipped values, and the waypoint is unbound
+    /// from its pose variable since it no longer matches it.
+    #[must_use]
+    pub fn flipped(&self, field: &Field) -> Waypoint<T> {
+        Waypoint {
+            x: flip_value(&self.x, "m", |x| field.flip_x(x)),
+            y: flip_value(&self.y, "m", |y| field.flip_y(y)),
+            heading: flip_value(&self.heading, "rad", |h| field.flip_heading(h)),
+            pose: None,
+            ..self.clone()
+        }
+    }
+}
+
+impl<T: SnapshottableType> ConstraintData<T> {
+    /// The constraint as seen from the other alliance's side of `field`.
+    ///
+    /// Only constraints with field positions change. Expressions for those positions
+    /// are replaced by their flipped values.
+    #[must_use]
+    pub fn flipped(&self, field: &Field) -> ConstraintData<T> {
+        let flip_x = |x: &T| flip_value(x, "m", |x| field.flip_x(x));
+        let flip_y = |y: &T| flip_value(y, "m", |y| field.flip_y(y));
+        match self {
+            ConstraintData::PointAt {
+                x,
+                y,
+                tolerance,
+                flip,
+            } => ConstraintData::PointAt {
+                x: flip_x(x),
+                y: flip_y(y),
+                tolerance: tolerance.clone(),
+                flip: *flip,
+            },
+            ConstraintData::KeepInCircle { x, y, r } => ConstraintData::KeepInCircle {
+                x: flip_x(x),
+                y: flip_y(y),
+                r: r.clone(),
+            },
+            ConstraintData::KeepOutCircle { x, y, r } => ConstraintData::KeepOutCircle {
+                x: flip_x(x),
+                y: flip_y(y),
+                r: r.clone(),
+            },
+            // The rectangle's origin is its minimum corner, so it moves to the opposite
+            // corner of the flipped rectangle.
+            ConstraintData::KeepInRectangle { x, y, w, h } => ConstraintData::KeepInRectangle {
+                x: T::fill_in_value(field.flip_x(x.snapshot() + w.snapshot()), "m"),
+                y: match field.flipper {
+                    Flipper::Mirrored => y.clone(),
+                    Flipper::RotateAround => {
+                        T::fill_in_value(field.flip_y(y.snapshot() + h.snapshot()), "m")
+                    }
+                },
+                w: w.clone(),
+                h: h.clone(),
+            },
+            _ => self.clone(),
+        }
+    }
+}
+
+impl<T: SnapshottableType> Parameters<T> {
+    /// The parameters as seen from the other alliance's side of `field`,
+    /// see [`Waypoint::flipped`] and [`ConstraintData::flipped`].
+    #[must_use]
+    pub fn flipped(&self, field: &Field) -> Parameters<T> {
+        Parameters {
+            waypoints: self.waypoints.iter().map(|w| w.flipped(field)).collect(),
+            constraints: self
+                .constraints
+                .iter()
+                .map(|c| Constraint {
+                    data: c.data.flipped(field),
+                    ..c.clone()
+                })
+                .collect(),
+            target_dt: self.target_dt.clone(),
+        }
+    }
+}
+
+impl TrajectoryFile {
+    /// The trajectory file as seen from the other alliance's side of `field`.
+    ///
+    /// The parameters and their snapshot are flipped alike, so a flipped trajectory
+    /// that was up to date stays up to date.
+    #[must_use]
+    pub fn flipped(&self, field: &Field) -> TrajectoryFile {
+        TrajectoryFile {
+            snapshot: self.snapshot.as_ref().map(|s| s.flipped(field)),
+            params: self.params.flipped(field),
+            trajectory: self.trajectory.flipped(field),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::spec::{trajectory::WaypointID, Expr};
+
+    fn swerve() -> Sample {
+        Sample::Swerve {
+            t: 1.0,
+            x: 1.0,
+            y: 2.0,
+            heading: FRAC_PI_2,
+            vx: 1.0,
+            vy: 2.0,
+            omega: 3.0,
+            ax: 4.0,
+            ay: 5.0,
+            alpha: 6.0,
+            fx: [1.0, 2.0, 3.0, 4.0],
+            fy: [5.0, 6.0, 7.0, 8.0],
+        }
+    }
+
+    #[test]
+    fn samples() {
+        let mirrored = Field::for_year(2024).unwrap();
+        let Sample::Swerve {
+            x,
+            y,
+            heading,
+            vx,
+            vy,
+            omega,
+            fx,
+            fy,
+            ..
+        } = swerve().flipped(&mirrored)
+        else {
+            panic!("expected a swerve sample");
+        };
+        assert_eq!((x, y, vx, vy, omega), (16.5811 - 1.0, 2.0, -1.0, 2.0, -3.0));
+        assert!((heading - FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(fx, [-4.0, -3.0, -2.0, -1.0]);
+        assert_eq!(fy, [8.0, 7.0, 6.0, 5.0]);
+
+        let rotated = Field::default();
+        let Sample::Swerve {
+            y,
+            heading,
+            vy,
+            omega,
+            fx,
+            ..
+        } = swerve().flipped(&rotated)
+        else {
+            panic!("expected a swerve sample");
+        };
+        assert_eq!((y, vy, omega), (8.052 - 2.0, -2.0, 3.0));
+        assert!((heading + FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(fx, [-1.0, -2.0, -3.0, -4.0]);
+
+        for field in [mirrored, rotated] {
+            let Sample::Swerve { x, fy, .. } = swerve().flipped(&field).flipped(&field) else {
+                panic!("expected a swerve sample");
+            };
+            assert!((x - 1.0).abs() < 1e-12);
+            assert_eq!(fy, [5.0, 6.0, 7.0, 8.0]);
+        }
+    }
+
+    #[test]
+    fn parameters() {
+        let field = Field::default();
+        let expr = |val: f64| Expr::new(&format!("{val} m"), val);
+        let params = Parameters {
+            waypoints: vec![Waypoint {
+                x: expr(1.0),
+                y: expr(2.0),
+                heading: Expr::new("0 rad", 0.0),
+                pose: Some("start".to_string()),
+                intervals: 10,
+                split: false,
+                fix_translation: true,
+                fix_heading: true,
+                override_intervals: false,
+                is_initial_guess: false,
+            }],
+            constraints: vec![Constraint {
+                from: WaypointID::First,
+                to: None,
+                data: ConstraintData::KeepInRectangle {
+                    x: expr(1.0),
+                    y: expr(1.0),
+                    w: expr(2.0),
+                    h: expr(3.0),
+                },
+                enabled: true,
+            }],
+            target_dt: Expr::new("0.05 s", 0.05),
+        };
+        let flipped = params.flipped(&field);
+        let waypoint = &flipped.waypoints[0];
+        assert_eq!(waypoint.x.val, 17.548 - 1.0);
+        assert_eq!(waypoint.x.exp, format!("{} m", 17.548 - 1.0));
+        assert_eq!(waypoint.heading.val, PI);
+        assert_eq!(waypoint.pose, None);
+        let ConstraintData::KeepInRectangle { x, y, w, .. } = &flipped.constraints[0].data else {
+            panic!("expected a rectangle");
+        };
+        assert_eq!((x.val, w.val), (17.548 - 3.0, 2.0));
+        assert!((y.val - (8.052 - 4.0)).abs() < 1e-12);
+    }
+}
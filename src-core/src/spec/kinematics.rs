@@ -0,0 +1,193 @@
+//! Swerve module states from trajectory samples, so robot code can command
+//! each module directly.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    project::RobotConfig,
+    trajectory::{Sample, Trajectory},
+};
+
+/// Below this speed a module's azimuth is taken from its force instead,
+/// since its direction of travel is undefined.
+///
+/// Units: meters / second
+const STOPPED_SPEED: f64 = 1e-6;
+
+/// The state of a swerve module at a sample.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleState {
+    /// The speed of the wheel along the ground.
+    ///
+    /// Units: meters / second
+    pub speed: f64,
+    /// The direction the wheel is travelling in, relative to the robot.
+    ///
+    /// Units: radians
+    pub azimuth: f64,
+    /// How fast the azimuth is turning.
+    ///
+    /// Units: radians / second
+    pub azimuth_rate: f64,
+    /// The torque on the wheel from the force along its direction of travel.
+    ///
+    /// Units: newton meters
+    pub torque: f64,
+}
+
+/// `(x, y)` rotated by `angle` radians.
+fn rotate((x, y): (f64, f64), angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    (x * cos - y * sin, x * sin + y * cos)
+}
+
+impl Sample {
+    /// The state of each swerve module, in the order of
+    /// [`RobotConfig::module_translations`], or `None` for a differential sample.
+    ///
+    /// Module velocities and accelerations come from the chassis velocity and
+    /// acceleration, and wheel torques from the module forces and the wheel radius.
+    #[must_use]
+    pub fn module_states(&self, config: &RobotConfig<f64>) -> Option<Vec<ModuleState>> {
+        let Sample::Swerve {
+            heading,
+            vx,
+            vy,
+            omega,
+            ax,
+            ay,
+            alpha,
+            fx,
+            fy,
+            ..
+        } = *self
+        else {
+            return None;
+        };
+        // The chassis velocity and acceleration relative to the robot,
+        // which rotates at omega underneath them
+        let velocity = rotate((vx, vy), -heading);
+        let acceleration = rotate((ax + omega * vy, ay - omega * vx), -heading);
+        Some(
+            config
+                .module_translations()
+                .iter()
+                .enumerate()
+                .map(|(i, translation)| {
+                    let (vx, vy) = (
+                        velocity.0 - omega * translation.y,
+                        velocity.1 + omega * translation.x,
+                    );
+                    let (ax, ay) = (
+                        acceleration.0 - alpha * translation.y,
+                        acceleration.1 + alpha * translation.x,
+                    );
+                    let force = rotate((fx[i], fy[i]), -heading);
+                    let speed = vx.hypot(vy);
+                    let (azimuth, azimuth_rate) = if speed > STOPPED_SPEED {
+                        (vy.atan2(vx), (vx * ay - vy * ax) / (speed * speed))
+                    } else {
+                        (force.1.atan2(force.0), 0.0)
+                    };
+                    let (sin, cos) = azimuth.sin_cos();
+                    ModuleState {
+                        speed,
+                        azimuth,
+                        azimuth_rate,
+                        torque: (force.0 * cos + force.1 * sin) * config.radius,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Trajectory {
+    /// The state of each swerve module at each sample, see [`Sample::module_states`].
+    ///
+    /// Empty for a differential trajectory.
+    #[must_use]
+    pub fn module_states(&self, config: &RobotConfig<f64>) -> Vec<Vec<ModuleState>> {
+        self.samples
+            .iter()
+            .map_while(|sample| sample.module_states(config))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::{generation::angle_modulus, spec::fixtures::swerve_config};
+
+    fn swerve(heading: f64, vx: f64, omega: f64, fx: f64) -> Sample {
+        Sample::Swerve {
+            t: 0.0,
+            x: 0.0,
+            y: 0.0,
+            heading,
+            vx,
+            vy: 0.0,
+            omega,
+            ax: 0.0,
+            ay: 0.0,
+            alpha: 0.0,
+            fx: [fx; 4],
+            fy: [0.0; 4],
+        }
+    }
+
+    #[test]
+    fn module_states() {
+        let config = swerve_config();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        // Driving along the field's x axis while facing its y axis
+        let states = swerve(FRAC_PI_2, 2.0, 0.0, 10.0)
+            .module_states(&config)
+            .unwrap();
+        assert_eq!(states.len(), 4);
+        for state in &states {
+            assert!(close(state.speed, 2.0));
+            assert!(close(state.azimuth, -FRAC_PI_2));
+            assert_eq!(state.azimuth_rate, 0.0);
+            assert!(close(state.torque, 10.0 * config.radius));
+        }
+
+        // Spinning in place, every module moves perpendicular to its translation
+        let states = swerve(0.0, 0.0, 1.0, 0.0).module_states(&config).unwrap();
+        for (state, translation) in states.iter().zip(config.module_translations()) {
+            assert!(close(state.speed, translation.x.hypot(translation.y)));
+            let turn = angle_modulus(state.azimuth - translation.y.atan2(translation.x));
+            assert!(close(turn, FRAC_PI_2));
+        }
+
+        // Turning left without rotating
+        let mut turning = swerve(0.0, 1.0, 0.0, 0.0);
+        let Sample::Swerve { ay, .. } = &mut turning else {
+            unreachable!();
+        };
+        *ay = 1.0;
+        for state in turning.module_states(&config).unwrap() {
+            assert!(close(state.azimuth_rate, 1.0));
+        }
+
+        let differential = Sample::DifferentialDrive {
+            t: 0.0,
+            x: 0.0,
+            y: 0.0,
+            heading: 0.0,
+            vl: 0.0,
+            vr: 0.0,
+            omega: 0.0,
+            al: 0.0,
+            ar: 0.0,
+            fl: 0.0,
+            fr: 0.0,
+        };
+        assert!(differential.module_states(&config).is_none());
+    }
+}
@@ -0,0 +1,514 @@
+//! Checks for trajectory parameters that the generator would ignore or can never satisfy.
+//!
+//! These checks only look at the parameters and the robot config, so they are cheap
+//! enough to run on every edit, unlike a full solve.
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    project::{Bumper, RobotConfig},
+    trajectory::{Constraint, ConstraintData, ConstraintScope, Parameters, Waypoint, WaypointID},
+    SnapshottableType,
+};
+
+/// How serious a [`Lint`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The trajectory can be generated, but not the way the user likely intended.
+    Warning,
+    /// The trajectory can't be generated.
+    Error,
+}
+
+/// A problem found in a trajectory's parameters.
+///
+/// Waypoint and constraint indices refer to positions in [`Parameters::waypoints`]
+/// and [`Parameters::constraints`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Lint {
+    /// The trajectory has fewer than two waypoints.
+    TooFewWaypoints { count: usize },
+    /// The target dt is not positive.
+    InvalidTargetDt,
+    /// A robot config value that must be positive isn't.
+    InvalidConfig { field: String },
+    /// A waypoint overrides its interval count with zero.
+    ZeroIntervals { waypoint: usize },
+    /// A constraint refers to a waypoint index that doesn't exist.
+    WaypointOutOfRange { constraint: usize, index: usize },
+    /// A constraint that only applies to segments is placed on a single waypoint,
+    /// so it is ignored.
+    MissingSegment { constraint: usize },
+    /// A constraint that only applies to waypoints is placed on a segment,
+    /// so it is ignored.
+    UnexpectedSegment { constraint: usize },
+    /// A constraint property is out of its valid range.
+    InvalidConstraintValue { constraint: usize, field: String },
+    /// A keep-in region is too small to fit the robot's bumpers in any orientation.
+    RegionTooSmall { constraint: usize },
+    /// A fixed waypoint puts the robot outside a keep-in region that applies to it.
+    OutsideKeepIn { constraint: usize, waypoint: usize },
+    /// A fixed waypoint puts the robot inside a keep-out region that applies to it.
+    InsideKeepOut { constraint: usize, waypoint: usize },
+}
+
+impl Lint {
+    /// How serious the problem is.
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::MissingSegment { .. } | Self::UnexpectedSegment { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// The index of the waypoint the problem is at, if any.
+    #[must_use]
+    pub const fn waypoint(&self) -> Option<usize> {
+        match self {
+            Self::ZeroIntervals { waypoint }
+            | Self::OutsideKeepIn { waypoint, .. }
+            | Self::InsideKeepOut { waypoint, .. } => Some(*waypoint),
+            _ => None,
+        }
+    }
+
+    /// The index of the constraint the problem is in, if any.
+    #[must_use]
+    pub const fn constraint(&self) -> Option<usize> {
+        match self {
+            Self::WaypointOutOfRange { constraint, .. }
+            | Self::MissingSegment { constraint }
+            | Self::UnexpectedSegment { constraint }
+            | Self::InvalidConstraintValue { constraint, .. }
+            | Self::RegionTooSmall { constraint }
+            | Self::OutsideKeepIn { constraint, .. }
+            | Self::InsideKeepOut { constraint, .. } => Some(*constraint),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewWaypoints { count } => {
+                write!(f, "At least 2 waypoints are required, found {count}")
+            }
+            Self::InvalidTargetDt => write!(f, "The target dt must be positive"),
+            Self::InvalidConfig { field } => write!(f, "Robot config {field} must be positive"),
+            Self::ZeroIntervals { waypoint } => {
+                write!(f, "Waypoint {waypoint} overrides its intervals with 0")
+            }
+            Self::WaypointOutOfRange { constraint, index } => write!(
+                f,
+                "Constraint {constraint} refers to waypoint {index}, which doesn't exist"
+            ),
+            Self::MissingSegment { constraint } => write!(
+                f,
+                "Constraint {constraint} only applies to segments and is ignored on a single waypoint"
+            ),
+            Self::UnexpectedSegment { constraint } => write!(
+                f,
+                "Constraint {constraint} only applies to waypoints and is ignored on a segment"
+            ),
+            Self::InvalidConstraintValue { constraint, field } => {
+                write!(f, "Constraint {constraint} has an invalid {field}")
+            }
+            Self::RegionTooSmall { constraint } => write!(
+                f,
+                "Constraint {constraint} is too small to contain the robot's bumpers"
+            ),
+            Self::OutsideKeepIn {
+                constraint,
+                waypoint,
+            } => write!(
+                f,
+                "Waypoint {waypoint} is outside the region of constraint {constraint}"
+            ),
+            Self::InsideKeepOut {
+                constraint,
+                waypoint,
+            } => write!(
+                f,
+                "Waypoint {waypoint} is inside the region of constraint {constraint}"
+            ),
+        }
+    }
+}
+
+/// The points of the robot that must satisfy a region constraint at `waypoint`.
+///
+/// If the heading is free, only the center is certain to be at a known position,
+/// otherwise the bumper corners are included too.
+fn footprint(waypoint: &Waypoint<f64>, bumper: &Bumper<f64>) -> Vec<(f64, f64)> {
+    let mut points = vec![(waypoint.x, waypoint.y)];
+    if !waypoint.fix_heading {
+        return points;
+    }
+    points.extend(bumper.corners(waypoint.x, waypoint.y, waypoint.heading));
+    points
+}
+
+/// The indices of the waypoints a constraint applies to, or `None` if it is ignored.
+fn resolve(
+    i: usize,
+    constraint: &Constraint<f64>,
+    count: usize,
+    lints: &mut Vec<Lint>,
+) -> Option<(usize, Option<usize>)> {
+    let mut out_of_range = |id: WaypointID| {
+        let idx = id.get_idx(count);
+        if let (None, WaypointID::Idx(index)) = (idx, id) {
+            lints.push(Lint::WaypointOutOfRange {
+                constraint: i,
+                index,
+            });
+        }
+        idx
+    };
+    let from = out_of_range(constraint.from);
+    let to = match constraint.to {
+        Some(id) => Some(out_of_range(id)?),
+        None => None,
+    };
+    let from = from?;
+    let to = to.filter(|to| *to != from);
+    match (constraint.data.scope(), to) {
+        (ConstraintScope::Segment, None) => {
+            lints.push(Lint::MissingSegment { constraint: i });
+            None
+        }
+        (ConstraintScope::Waypoint, Some(_)) => {
+            lints.push(Lint::UnexpectedSegment { constraint: i });
+            None
+        }
+        (_, Some(to)) => Some((from.min(to), Some(from.max(to)))),
+        (_, None) => Some((from, None)),
+    }
+}
+
+fn lint_values(i: usize, data: &ConstraintData<f64>, lints: &mut Vec<Lint>) {
+    // (field, value, whether zero is valid)
+    let fields = match *data {
+        ConstraintData::MaxVelocity { max }
+        | ConstraintData::MaxAcceleration { max }
+        | ConstraintData::MaxAngularVelocity { max } => vec![("max", max, true)],
+        ConstraintData::PointAt { tolerance, .. } | ConstraintData::KeepInLane { tolerance } => {
+            vec![("tolerance", tolerance, true)]
+        }
+        ConstraintData::KeepInCircle { r, .. } | ConstraintData::KeepOutCircle { r, .. } => {
+            vec![("r", r, false)]
+        }
+        ConstraintData::KeepInRectangle { w, h, .. } => vec![("w", w, false), ("h", h, false)],
+        ConstraintData::StopPoint {} => vec![],
+    };
+    for (field, value, zero_allowed) in fields {
+        if value.is_nan() || value < 0.0 || (value == 0.0 && !zero_allowed) {
+            lints.push(Lint::InvalidConstraintValue {
+                constraint: i,
+                field: field.to_string(),
+            });
+        }
+    }
+}
+
+fn lint_region(
+    i: usize,
+    data: &ConstraintData<f64>,
+    waypoints: &[(usize, &Waypoint<f64>)],
+    config: &RobotConfig<f64>,
+    lints: &mut Vec<Lint>,
+) {
+    let bumper = &config.bumper;
+    if let ConstraintData::KeepInCircle { r, .. } = *data {
+        if r < ((bumper.front + bumper.back) / 2.0).hypot(bumper.side) {
+            lints.push(Lint::RegionTooSmall { constraint: i });
+            return;
+        }
+    }
+    // Whether a point of the robot satisfies the constraint
+    let satisfies = |(px, py): &(f64, f64)| match *data {
+        ConstraintData::KeepInCircle { x, y, r } => (px - x).hypot(py - y) <= r,
+        ConstraintData::KeepInRectangle { x, y, w, h } => {
+            (x..=x + w).contains(px) && (y..=y + h).contains(py)
+        }
+        ConstraintData::KeepOutCircle { x, y, r } => (px - x).hypot(py - y) >= r,
+        _ => true,
+    };
+    for &(waypoint, w) in waypoints {
+        if footprint(w, bumper).iter().all(satisfies) {
+            continue;
+        }
+        lints.push(match data {
+            ConstraintData::KeepOutCircle { .. } => Lint::InsideKeepOut {
+                constraint: i,
+                waypoint,
+            },
+            _ => Lint::OutsideKeepIn {
+                constraint: i,
+                waypoint,
+            },
+        });
+    }
+}
+
+/// The waypoints strictly between the ends of a lane that are off its center line
+/// by more than `tolerance`.
+fn lint_lane(
+    i: usize,
+    tolerance: f64,
+    (from, to): (usize, usize),
+    waypoints: &[Waypoint<f64>],
+    lints: &mut Vec<Lint>,
+) {
+    let (start, end) = (&waypoints[from], &waypoints[to]);
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return;
+    }
+    for (waypoint, w) in waypoints.iter().enumerate().take(to).skip(from + 1) {
+        let distance = (dx * (w.y - start.y) - dy * (w.x - start.x)).abs() / length;
+        if w.fix_translation && distance > tolerance {
+            lints.push(Lint::OutsideKeepIn {
+                constraint: i,
+                waypoint,
+            });
+        }
+    }
+}
+
+fn lint_config(config: &RobotConfig<f64>, lints: &mut Vec<Lint>) {
+    for (field, value) in [
+        ("mass", config.mass),
+        ("inertia", config.inertia),
+        ("gearing", config.gearing),
+        ("radius", config.radius),
+        ("vmax", config.vmax),
+        ("tmax", config.tmax),
+        ("cof", config.cof),
+    ] {
+        if value.is_nan() || value <= 0.0 {
+            lints.push(Lint::InvalidConfig {
+                field: field.to_string(),
+            });
+        }
+    }
+}
+
+/// Check `params` against `config` for problems that would make the generator ignore
+/// part of the parameters or fail to find a solution.
+///
+/// Disabled constraints are not checked, since the generator never sees them.
+/// The lints are ordered by where they were found: the config, then the waypoints,
+/// then the constraints.
+pub fn lint<T: SnapshottableType>(params: &Parameters<T>, config: &RobotConfig<T>) -> Vec<Lint> {
+    let params = params.snapshot();
+    let config = config.snapshot();
+    let mut lints = Vec::new();
+    lint_config(&config, &mut lints);
+
+    let waypoints = &params.waypoints;
+    if waypoints.len() < 2 {
+        lints.push(Lint::TooFewWaypoints {
+            count: waypoints.len(),
+        });
+    }
+    if params.target_dt.is_nan() || params.target_dt <= 0.0 {
+        lints.push(Lint::InvalidTargetDt);
+    }
+    for (waypoint, w) in waypoints.iter().enumerate() {
+        if w.override_intervals && w.intervals == 0 && waypoint + 1 < waypoints.len() {
+            lints.push(Lint::ZeroIntervals { waypoint });
+        }
+    }
+
+    for (i, constraint) in params.constraints.iter().enumerate() {
+        if !constraint.enabled {
+            continue;
+        }
+        lint_values(i, &constraint.data, &mut lints);
+        let Some((from, to)) = resolve(i, constraint, waypoints.len(), &mut lints) else {
+            continue;
+        };
+        if let (ConstraintData::KeepInLane { tolerance }, Some(to)) = (constraint.data, to) {
+            lint_lane(i, tolerance, (from, to), waypoints, &mut lints);
+            continue;
+        }
+        let fixed = waypoints
+            .iter()
+            .enumerate()
+            .take(to.unwrap_or(from) + 1)
+            .skip(from)
+            .filter(|(_, w)| w.fix_translation)
+            .collect::<Vec<_>>();
+        lint_region(i, &constraint.data, &fixed, &config, &mut lints);
+    }
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::project::ProjectFile;
+
+    fn waypoint(x: f64, y: f64) -> Waypoint<f64> {
+        Waypoint {
+            x,
+            y,
+            heading: 0.0,
+            pose: None,
+            intervals: 20,
+            split: false,
+            fix_translation: true,
+            fix_heading: true,
+            override_intervals: false,
+            is_initial_guess: false,
+        }
+    }
+
+    fn constraint(
+        from: WaypointID,
+        to: Option<WaypointID>,
+        data: ConstraintData<f64>,
+    ) -> Constraint<f64> {
+        Constraint {
+            from,
+            to,
+            data,
+            enabled: true,
+        }
+    }
+
+    fn params(constraints: Vec<Constraint<f64>>) -> Parameters<f64> {
+        Parameters {
+            waypoints: vec![waypoint(0.0, 0.0), waypoint(2.0, 1.0), waypoint(4.0, 0.0)],
+            constraints,
+            target_dt: 0.05,
+        }
+    }
+
+    #[test]
+    fn clean() {
+        let config = ProjectFile::default().config;
+        let params = params(vec![
+            constraint(
+                WaypointID::First,
+                Some(WaypointID::Last),
+                ConstraintData::MaxVelocity { max: 3.0 },
+            ),
+            constraint(WaypointID::Idx(1), None, ConstraintData::StopPoint {}),
+            constraint(
+                WaypointID::First,
+                Some(WaypointID::Last),
+                ConstraintData::KeepInRectangle {
+                    x: -1.0,
+                    y: -1.0,
+                    w: 6.0,
+                    h: 3.0,
+                },
+            ),
+        ]);
+        assert_eq!(lint(&params, &config.snapshot()), vec![]);
+    }
+
+    #[test]
+    fn problems() {
+        let config = ProjectFile::default().config.snapshot();
+        let mut params = params(vec![
+            constraint(
+                WaypointID::Idx(5),
+                None,
+                ConstraintData::MaxVelocity { max: -1.0 },
+            ),
+            constraint(
+                WaypointID::Idx(1),
+                None,
+                ConstraintData::KeepInLane { tolerance: 0.1 },
+            ),
+            constraint(
+                WaypointID::First,
+                Some(WaypointID::Last),
+                ConstraintData::StopPoint {},
+            ),
+            constraint(
+                WaypointID::First,
+                Some(WaypointID::Last),
+                ConstraintData::KeepInLane { tolerance: 0.5 },
+            ),
+            constraint(
+                WaypointID::Idx(1),
+                None,
+                ConstraintData::KeepInCircle {
+                    x: 0.0,
+                    y: 0.0,
+                    r: 1.0,
+                },
+            ),
+            constraint(
+                WaypointID::Last,
+                None,
+                ConstraintData::KeepOutCircle {
+                    x: 4.0,
+                    y: 0.5,
+                    r: 0.6,
+                },
+            ),
+            constraint(
+                WaypointID::First,
+                None,
+                ConstraintData::KeepInCircle {
+                    x: 0.0,
+                    y: 0.0,
+                    r: 0.1,
+                },
+            ),
+        ]);
+        params.constraints.push(Constraint {
+            enabled: false,
+            ..constraint(WaypointID::Idx(9), None, ConstraintData::StopPoint {})
+        });
+        params.waypoints[0].override_intervals = true;
+        params.waypoints[0].intervals = 0;
+
+        let lints = lint(&params, &config);
+        assert_eq!(
+            lints,
+            vec![
+                Lint::ZeroIntervals { waypoint: 0 },
+                Lint::InvalidConstraintValue {
+                    constraint: 0,
+                    field: "max".to_string()
+                },
+                Lint::WaypointOutOfRange {
+                    constraint: 0,
+                    index: 5
+                },
+                Lint::MissingSegment { constraint: 1 },
+                Lint::UnexpectedSegment { constraint: 2 },
+                Lint::OutsideKeepIn {
+                    constraint: 3,
+                    waypoint: 1
+                },
+                Lint::OutsideKeepIn {
+                    constraint: 4,
+                    waypoint: 1
+                },
+                Lint::InsideKeepOut {
+                    constraint: 5,
+                    waypoint: 2
+                },
+                Lint::RegionTooSmall { constraint: 6 },
+            ]
+        );
+        assert_eq!(lints[1].severity(), Severity::Error);
+        assert_eq!(lints[3].severity(), Severity::Warning);
+        assert_eq!(lints[5].waypoint(), Some(1));
+        assert_eq!(lints[5].constraint(), Some(3));
+    }
+}
@@ -1,9 +1,22 @@
 use std::fmt::Debug;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+pub mod diff;
+pub mod expression;
+pub mod feasibility;
+pub mod field_dimensions;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod flip;
+pub mod kinematics;
+pub mod lint;
+pub mod pathplanner;
 pub mod project;
 pub mod project_schema_version;
+pub mod schema;
+pub mod stats;
 pub mod traj_schema_version;
 pub mod trajectory;
 pub mod upgraders;
@@ -38,7 +51,7 @@ impl SnapshottableType for f64 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct Expr {
     pub exp: String,
     pub val: f64,
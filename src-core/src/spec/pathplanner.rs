@@ -0,0 +1,11 @@
+//! Conversion to and from [PathPlanner](https://pathplanner.dev) files,
+//! for teams that mix PathPlanner autos with Choreo trajectories.
+
+mod command;
+mod path;
+
+pub use command::{AutoFile, Command};
+pub use path::{
+    ConstraintZone, PathConstraints, PathEventMarker, PathFile, PathState, PathWaypoint, Point,
+    RotationTarget,
+};
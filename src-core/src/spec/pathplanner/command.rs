@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    spec::{trajectory::PplibCommand, Expr, SnapshottableType},
+    ChoreoError, ChoreoResult,
+};
+
+/// A command in PathPlanner's auto format.
+///
+/// This matches [`PplibCommand`] except that wait times are plain seconds,
+/// and autos can also follow a path by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "data")]
+pub enum Command {
+    Named {
+        name: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Wait {
+        wait_time: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Path {
+        path_name: Option<String>,
+    },
+    Sequential {
+        commands: Vec<Command>,
+    },
+    Parallel {
+        commands: Vec<Command>,
+    },
+    Race {
+        commands: Vec<Command>,
+    },
+    Deadline {
+        commands: Vec<Command>,
+    },
+}
+
+impl Command {
+    /// The names of the named commands in the tree, in the order they first appear.
+    #[must_use]
+    pub fn named_commands(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_names(&mut names);
+        names
+    }
+
+    fn collect_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Command::Named { name: Some(name) } => {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+            Command::Sequential { commands }
+            | Command::Parallel { commands }
+            | Command::Race { commands }
+            | Command::Deadline { commands } => {
+                for command in commands {
+                    command.collect_names(names);
+                }
+            }
+            Command::Named { name: None } | Command::Wait { .. } | Command::Path { .. } => {}
+        }
+    }
+}
+
+impl From<&PplibCommand> for Command {
+    fn from(command: &PplibCommand) -> Self {
+        let convert = |commands: &Vec<PplibCommand>| commands.iter().map(Command::from).collect();
+        match command {
+            PplibCommand::Named { name } => Command::Named { name: name.clone() },
+            PplibCommand::Wait { wait_time } => Command::Wait {
+                wait_time: wait_time.snapshot(),
+            },
+            PplibCommand::Sequential { commands } => Command::Sequential {
+                commands: convert(commands),
+            },
+            PplibCommand::Parallel { commands } => Command::Parallel {
+                commands: convert(commands),
+            },
+            PplibCommand::Race { commands } => Command::Race {
+                commands: convert(commands),
+            },
+            PplibCommand::Deadline { commands } => Command::Deadline {
+                commands: convert(commands),
+            },
+        }
+    }
+}
+
+impl TryFrom<&Command> for PplibCommand {
+    type Error = ChoreoError;
+
+    /// Fails if the tree follows a path, which event markers can't do.
+    fn try_from(command: &Command) -> ChoreoResult<Self> {
+        let convert = |commands: &Vec<Command>| {
+            commands
+                .iter()
+                .map(PplibCommand::try_from)
+                .collect::<ChoreoResult<Vec<_>>>()
+        };
+        Ok(match command {
+            Command::Named { name } => PplibCommand::Named { name: name.clone() },
+            Command::Wait { wait_time } => PplibCommand::Wait {
+                wait_time: Expr::fill_in_value(*wait_time, "s"),
+            },
+            Command::Path { path_name } => {
+                return Err(ChoreoError::PathPlanner(format!(
+                    "Event markers can't follow paths, found path command {}",
+                    path_name.as_deref().unwrap_or("with no path")
+                )))
+            }
+            Command::Sequential { commands } => PplibCommand::Sequential {
+                commands: convert(commands)?,
+            },
+            Command::Parallel { commands } => PplibCommand::Parallel {
+                commands: convert(commands)?,
+            },
+            Command::Race { commands } => PplibCommand::Race {
+                commands: convert(commands)?,
+            },
+            Command::Deadline { commands } => PplibCommand::Deadline {
+                commands: convert(commands)?,
+            },
+        })
+    }
+}
+
+/// A PathPlanner `.auto` file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoFile {
+    pub version: String,
+    pub command: Command,
+    #[serde(default)]
+    pub reset_odom: bool,
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Whether the paths in the auto are Choreo trajectories.
+    #[serde(default)]
+    pub choreo_auto: bool,
+}
+
+impl AutoFile {
+    /// The version of the auto format written by [`AutoFile::new`].
+    pub const VERSION: &'static str = "2025.0";
+
+    /// An auto that runs `command`, following Choreo trajectories by name.
+    #[must_use]
+    pub fn new(command: Command) -> Self {
+        Self {
+            version: Self::VERSION.to_string(),
+            command,
+            reset_odom: true,
+            folder: None,
+            choreo_auto: true,
+        }
+    }
+
+    /// Parse an auto from the contents of a `.auto` file.
+    pub fn from_content(content: &str) -> ChoreoResult<AutoFile> {
+        serde_json::from_str(content).map_err(Into::into)
+    }
+
+    /// The contents of a `.auto` file for the auto.
+    pub fn to_content(&self) -> ChoreoResult<String> {
+        serde_json::to_string_pretty(self).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> PplibCommand {
+        PplibCommand::Named {
+            name: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let event = PplibCommand::Sequential {
+            commands: vec![
+                named("intake"),
+                PplibCommand::Deadline {
+                    commands: vec![
+                        PplibCommand::Wait {
+                            wait_time: Expr::new("0.5 s", 0.5),
+                        },
+                        named("shoot"),
+                        named("intake"),
+                    ],
+                },
+                PplibCommand::Named { name: None },
+            ],
+        };
+        let command = Command::from(&event);
+        assert_eq!(command.named_commands(), vec!["intake", "shoot"]);
+
+        let auto =
+            AutoFile::from_content(&AutoFile::new(command.clone()).to_content().unwrap()).unwrap();
+        assert_eq!(auto.command, command);
+        assert!(auto.choreo_auto);
+
+        let imported = PplibCommand::try_from(&auto.command).unwrap();
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&event).unwrap()
+        );
+    }
+
+    #[test]
+    fn pathplanner_auto() {
+        let auto = AutoFile::from_content(
+            r#"{
+              "version": "2025.0",
+              "command": {
+                "type": "sequential",
+                "data": {
+                  "commands": [
+                    {"type": "path", "data": {"pathName": "Start"}},
+                    {"type": "wait", "data": {"waitTime": 1.5}},
+                    {"type": "race", "data": {"commands": [{"type": "named", "data": {"name": "score"}}]}}
+                  ]
+                }
+              },
+              "resetOdom": true,
+              "folder": null,
+              "choreoAuto": false
+            }"#,
+        )
+        .unwrap();
+        let Command::Sequential { commands } = &auto.command else {
+            panic!("expected a sequential command");
+        };
+        assert!(PplibCommand::try_from(&auto.command).is_err());
+        let PplibCommand::Wait { wait_time } = PplibCommand::try_from(&commands[1]).unwrap() else {
+            panic!("expected a wait command");
+        };
+        assert_eq!(wait_time.exp, "1.5 s");
+        assert_eq!(auto.command.named_commands(), vec!["score"]);
+    }
+}
@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    spec::{
+        trajectory::{
+            Constraint, ConstraintData, EventMarker, EventMarkerData, Parameters, PplibCommand,
+            Trajectory, TrajectoryFile, Waypoint, WaypointID,
+        },
+        Expr, SnapshottableType, TRAJ_SCHEMA_VERSION,
+    },
+    ChoreoResult,
+};
+
+use super::Command;
+
+/// A point on the field in meters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// An anchor of a PathPlanner path, with the bezier control points on either side of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathWaypoint {
+    pub anchor: Point,
+    pub prev_control: Option<Point>,
+    pub next_control: Option<Point>,
+}
+
+/// A heading the robot should reach partway along the path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationTarget {
+    /// The position along the path, where waypoint `i` is at `i`.
+    pub waypoint_relative_pos: f64,
+    pub rotation_degrees: f64,
+}
+
+/// The limits of a PathPlanner path or zone.
+///
+/// Angular limits are in degrees.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathConstraints {
+    pub max_velocity: f64,
+    pub max_acceleration: f64,
+    pub max_angular_velocity: f64,
+    pub max_angular_acceleration: f64,
+    #[serde(default)]
+    pub unlimited: bool,
+}
+
+/// A section of the path with its own limits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintZone {
+    pub name: String,
+    pub min_waypoint_relative_pos: f64,
+    pub max_waypoint_relative_pos: f64,
+    pub constraints: PathConstraints,
+}
+
+/// A command triggered partway along the path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathEventMarker {
+    pub name: String,
+    pub waypoint_relative_pos: f64,
+    #[serde(default)]
+    pub command: Option<Command>,
+}
+
+/// The state of the robot at the start or end of the path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathState {
+    /// Units: meters / second
+    pub velocity: f64,
+    /// Units: degrees
+    pub rotation: f64,
+}
+
+/// A PathPlanner `.path` file.
+///
+/// Fields Choreo has no use for, like point towards zones, are ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathFile {
+    pub version: serde_json::Value,
+    pub waypoints: Vec<PathWaypoint>,
+    #[serde(default)]
+    pub rotation_targets: Vec<RotationTarget>,
+    #[serde(default)]
+    pub constraint_zones: Vec<ConstraintZone>,
+    #[serde(default)]
+    pub event_markers: Vec<PathEventMarker>,
+    pub global_constraints: PathConstraints,
+    pub goal_end_state: PathState,
+    /// Called `previewStartingState` before 2025.
+    #[serde(default, alias = "previewStartingState")]
+    pub ideal_starting_state: Option<PathState>,
+    #[serde(default)]
+    pub use_default_constraints: bool,
+}
+
+/// A waypoint of the imported trajectory, at `pos` along the PathPlanner path.
+struct Anchor {
+    pos: f64,
+    point: Point,
+    heading: Option<f64>,
+}
+
+fn lerp(from: Point, to: Point, t: f64) -> Point {
+    Point {
+        x: from.x + (to.x - from.x) * t,
+        y: from.y + (to.y - from.y) * t,
+    }
+}
+
+impl PathFile {
+    /// Parse a path from the contents of a `.path` file.
+    pub fn from_content(content: &str) -> ChoreoResult<PathFile> {
+        serde_json::from_str(content).map_err(Into::into)
+    }
+
+    /// The point at `pos` along the bezier curve of the path.
+    fn point_at(&self, pos: f64) -> Point {
+        let last = self.waypoints.len().saturating_sub(1);
+        let pos = pos.clamp(0.0, last as f64);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let segment = (pos.floor() as usize).min(last.saturating_sub(1));
+        let (Some(start), Some(end)) =
+            (self.waypoints.get(segment), self.waypoints.get(segment + 1))
+        else {
+            return self
+                .waypoints
+                .first()
+                .map_or(Point { x: 0.0, y: 0.0 }, |w| w.anchor);
+        };
+        let t = pos - segment as f64;
+        let controls = [
+            start.anchor,
+            start.next_control.unwrap_or(start.anchor),
+            end.prev_control.unwrap_or(end.anchor),
+            end.anchor,
+        ];
+        // De Casteljau's algorithm
+        let quadratic = [0, 1, 2].map(|i| lerp(controls[i], controls[i + 1], t));
+        let linear = [0, 1].map(|i| lerp(quadratic[i], quadratic[i + 1], t));
+        lerp(linear[0], linear[1], t)
+    }
+
+    /// The waypoints of the imported trajectory, in order along the path.
+    fn anchors(&self) -> Vec<Anchor> {
+        let mut anchors = self
+            .waypoints
+            .iter()
+            .enumerate()
+            .map(|(i, waypoint)| Anchor {
+                pos: i as f64,
+                point: waypoint.anchor,
+                heading: None,
+            })
+            .collect::<Vec<_>>();
+        let last = anchors.len().saturating_sub(1) as f64;
+        if let Some(first) = anchors.first_mut() {
+            first.heading = self.ideal_starting_state.as_ref().map(|s| s.rotation);
+        }
+        if let Some(end) = anchors.last_mut() {
+            end.heading = Some(self.goal_end_state.rotation);
+        }
+        for target in &self.rotation_targets {
+            let pos = target.waypoint_relative_pos.clamp(0.0, last);
+            match anchors.iter_mut().find(|a| (a.pos - pos).abs() < 1e-6) {
+                Some(anchor) => anchor.heading = Some(target.rotation_degrees),
+                None => anchors.push(Anchor {
+                    pos,
+                    point: self.point_at(pos),
+                    heading: Some(target.rotation_degrees),
+                }),
+            }
+        }
+        anchors.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+        anchors
+    }
+
+    /// Import the path as a Choreo trajectory named `name`, ready to be generated.
+    ///
+    /// Anchors become waypoints, and rotation targets become heading-fixed waypoints on
+    /// the curve, or fix the heading of the anchor they fall on. The starting and goal
+    /// rotations fix the headings of the first and last waypoints, and stop points are
+    /// added where the path starts or ends at rest.
+    ///
+    /// Constraint zones become [`ConstraintData::MaxVelocity`] and
+    /// [`ConstraintData::MaxAcceleration`] segment constraints, widened out to the
+    /// nearest waypoints. The global constraints apply to the whole path unless the path
+    /// uses PathPlanner's defaults, which the project's robot config stands in for.
+    ///
+    /// Event markers target the nearest waypoint with no offset, since PathPlanner places
+    /// them by distance rather than time.
+    ///
+    /// Fails if an event marker's command follows a path.
+    pub fn to_trajectory(&self, name: &str) -> ChoreoResult<TrajectoryFile> {
+        let anchors = self.anchors();
+        let waypoints = anchors
+            .iter()
+            .map(|anchor| Waypoint {
+                x: Expr::fill_in_value(anchor.point.x, "m"),
+                y: Expr::fill_in_value(anchor.point.y, "m"),
+                heading: Expr::fill_in_value(anchor.heading.unwrap_or(0.0).to_radians(), "rad"),
+                pose: None,
+                intervals: 40,
+                split: false,
+                fix_translation: true,
+                fix_heading: anchor.heading.is_some(),
+                override_intervals: false,
+                is_initial_guess: false,
+            })
+            .collect::<Vec<_>>();
+
+        let nearest = |pos: f64| {
+            anchors
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| (a.pos - pos).abs().total_cmp(&(b.pos - pos).abs()))
+                .map_or(0, |(i, _)| i)
+        };
+        let at_or_before = |pos: f64| anchors.iter().rposition(|a| a.pos <= pos + 1e-6);
+        let at_or_after = |pos: f64| anchors.iter().position(|a| a.pos >= pos - 1e-6);
+
+        let mut constraints = Vec::new();
+        let constraint = |from, to, data| Constraint {
+            from,
+            to,
+            data,
+            enabled: true,
+        };
+        let limits = |constraints: &PathConstraints| {
+            [
+                ConstraintData::MaxVelocity {
+                    max: Expr::fill_in_value(constraints.max_velocity, "m / s"),
+                },
+                ConstraintData::MaxAcceleration {
+                    max: Expr::fill_in_value(constraints.max_acceleration, "m / s ^ 2"),
+                },
+            ]
+        };
+        if self
+            .ideal_starting_state
+            .as_ref()
+            .is_some_and(|s| s.velocity == 0.0)
+        {
+            constraints.push(constraint(
+                WaypointID::First,
+                None,
+                ConstraintData::StopPoint {},
+            ));
+        }
+        if self.goal_end_state.velocity == 0.0 {
+            constraints.push(constraint(
+                WaypointID::Last,
+                None,
+                ConstraintData::StopPoint {},
+            ));
+        }
+        if !self.use_default_constraints && !self.global_constraints.unlimited {
+            for data in limits(&self.global_constraints) {
+                constraints.push(constraint(WaypointID::First, Some(WaypointID::Last), data));
+            }
+        }
+        for zone in &self.constraint_zones {
+            if zone.constraints.unlimited {
+                continue;
+            }
+            let (Some(from), Some(to)) = (
+                at_or_before(zone.min_waypoint_relative_pos),
+                at_or_after(zone.max_waypoint_relative_pos),
+            ) else {
+                continue;
+            };
+            if from >= to {
+                continue;
+            }
+            for data in limits(&zone.constraints) {
+                constraints.push(constraint(
+                    WaypointID::Idx(from),
+                    Some(WaypointID::Idx(to)),
+                    data,
+                ));
+            }
+        }
+
+        let events = self
+            .event_markers
+            .iter()
+            .map(|marker| {
+                Ok(EventMarker {
+                    name: marker.name.clone(),
+                    from: EventMarkerData {
+                        target: Some(WaypointID::Idx(nearest(marker.waypoint_relative_pos))),
+                        target_timestamp: None,
+                        offset: Expr::fill_in_value(0.0, "s"),
+                    },
+                    event: marker
+                        .command
+                        .as_ref()
+                        .map(PplibCommand::try_from)
+                        .transpose()?,
+                })
+            })
+            .collect::<ChoreoResult<Vec<_>>>()?;
+
+        Ok(TrajectoryFile {
+            name: name.to_string(),
+            version: TRAJ_SCHEMA_VERSION,
+            snapshot: None,
+            params: Parameters {
+                waypoints,
+                constraints,
+                target_dt: Expr::fill_in_value(0.05, "s"),
+            },
+            trajectory: Trajectory {
+                sample_type: None,
+                waypoints: Vec::new(),
+                samples: Vec::new(),
+                splits: Vec::new(),
+            },
+            events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = r#"{
+      "version": "2025.0",
+      "waypoints": [
+        {"anchor": {"x": 1.0, "y": 1.0}, "prevControl": null, "nextControl": {"x": 2.0, "y": 1.0}, "isLocked": false, "linkedName": null},
+        {"anchor": {"x": 4.0, "y": 1.0}, "prevControl": {"x": 3.0, "y": 1.0}, "nextControl": {"x": 5.0, "y": 1.0}, "isLocked": false, "linkedName": null},
+        {"anchor": {"x": 7.0, "y": 1.0}, "prevControl": {"x": 6.0, "y": 1.0}, "nextControl": null, "isLocked": false, "linkedName": null}
+      ],
+      "rotationTargets": [
+        {"waypointRelativePos": 0.5, "rotationDegrees": 90.0},
+        {"waypointRelativePos": 1.0, "rotationDegrees": 45.0}
+      ],
+      "constraintZones": [
+        {
+          "name": "Slow",
+          "minWaypointRelativePos": 1.2,
+          "maxWaypointRelativePos": 1.8,
+          "constraints": {"maxVelocity": 1.0, "maxAcceleration": 2.0, "maxAngularVelocity": 540.0, "maxAngularAcceleration": 720.0, "nominalVoltage": 12.0, "unlimited": false}
+        }
+      ],
+      "pointTowardsZones": [],
+      "eventMarkers": [
+        {"name": "Intake", "waypointRelativePos": 1.9, "endWaypointRelativePos": null, "command": {"type": "named", "data": {"name": "intake"}}},
+        {"name": "Empty", "waypointRelativePos": 0.4, "endWaypointRelativePos": null, "command": null}
+      ],
+      "globalConstraints": {"maxVelocity": 3.0, "maxAcceleration": 3.0, "maxAngularVelocity": 540.0, "maxAngularAcceleration": 720.0, "nominalVoltage": 12.0, "unlimited": false},
+      "goalEndState": {"velocity": 0, "rotation": 180.0},
+      "reversed": false,
+      "folder": null,
+      "idealStartingState": {"velocity": 0, "rotation": 0.0},
+      "useDefaultConstraints": true
+    }"#;
+
+    #[test]
+    fn import() {
+        let trajectory = PathFile::from_content(PATH)
+            .unwrap()
+            .to_trajectory("Imported")
+            .unwrap();
+        assert_eq!(trajectory.name, "Imported");
+        let waypoints = &trajectory.params.waypoints;
+        assert_eq!(waypoints.len(), 4);
+        assert_eq!(
+            waypoints
+                .iter()
+                .map(|w| (w.x.val, w.y.val, w.fix_heading))
+                .collect::<Vec<_>>(),
+            vec![
+                (1.0, 1.0, true),
+                (2.5, 1.0, true),
+                (4.0, 1.0, true),
+                (7.0, 1.0, true)
+            ]
+        );
+        assert_eq!(waypoints[1].heading.val, 90f64.to_radians());
+        assert_eq!(waypoints[2].heading.val, 45f64.to_radians());
+        assert_eq!(waypoints[3].heading.val, 180f64.to_radians());
+
+        let constraints = &trajectory.params.constraints;
+        assert_eq!(constraints.len(), 4);
+        assert_eq!(constraints[0].from, WaypointID::First);
+        assert_eq!(constraints[1].from, WaypointID::Last);
+        assert_eq!(constraints[2].from, WaypointID::Idx(2));
+        assert_eq!(constraints[2].to, Some(WaypointID::Idx(3)));
+        let ConstraintData::MaxVelocity { max } = &constraints[2].data else {
+            panic!("expected a max velocity constraint");
+        };
+        assert_eq!(max.val, 1.0);
+
+        let events = &trajectory.events;
+        assert_eq!(events[0].from.target, Some(WaypointID::Idx(3)));
+        assert!(matches!(
+            &events[0].event,
+            Some(PplibCommand::Named { name: Some(name) }) if name == "intake"
+        ));
+        assert_eq!(events[1].from.target, Some(WaypointID::Idx(1)));
+        assert!(events[1].event.is_none());
+    }
+}
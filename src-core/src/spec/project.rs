@@ -1,11 +1,19 @@
 use std::collections::BTreeMap;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use trajoptlib::Translation2d;
 
-use super::{trajectory::DriveType, upgraders::upgrade_project_file, Expr, SnapshottableType};
+mod dependencies;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub use dependencies::{DependencyGraph, UndefinedReference};
+
+use super::{
+    expression::BaseDimensions, trajectory::DriveType, upgraders::upgrade_project_file, Expr,
+    SnapshottableType,
+};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
 pub enum Dimension {
     Number,
     Length,
@@ -19,23 +27,43 @@ pub enum Dimension {
     Torque,
     MoI,
 }
-#[derive(Debug, Serialize, Deserialize, Clone)]
+
+impl Dimension {
+    /// The SI base dimensions of values with this dimension.
+    #[must_use]
+    pub const fn base_dimensions(&self) -> BaseDimensions {
+        match self {
+            Dimension::Number => BaseDimensions::new(0, 0, 0, 0),
+            Dimension::Length => BaseDimensions::new(1, 0, 0, 0),
+            Dimension::LinVel => BaseDimensions::new(1, 0, -1, 0),
+            Dimension::LinAcc => BaseDimensions::new(1, 0, -2, 0),
+            Dimension::Angle => BaseDimensions::new(0, 0, 0, 1),
+            Dimension::AngVel => BaseDimensions::new(0, 0, -1, 1),
+            Dimension::AngAcc => BaseDimensions::new(0, 0, -2, 1),
+            Dimension::Time => BaseDimensions::new(0, 0, 1, 0),
+            Dimension::Mass => BaseDimensions::new(0, 1, 0, 0),
+            Dimension::Torque => BaseDimensions::new(2, 1, -2, 0),
+            Dimension::MoI => BaseDimensions::new(2, 1, 0, 0),
+        }
+    }
+}
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct Variable {
     pub dimension: Dimension,
     pub var: Expr,
 }
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct PoseVariable {
     pub x: Expr,
     pub y: Expr,
     pub heading: Expr,
 }
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct Variables {
     pub expressions: BTreeMap<String, Variable>,
     pub poses: BTreeMap<String, PoseVariable>,
 }
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy)]
 pub struct Bumper<T: SnapshottableType> {
     pub front: T,
     pub side: T,
@@ -51,7 +79,23 @@ impl<T: SnapshottableType> Bumper<T> {
         }
     }
 }
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+
+impl Bumper<f64> {
+    /// The corners of the bumpers for a robot at `(x, y)` facing `heading`,
+    /// clockwise from the front left.
+    #[must_use]
+    pub fn corners(&self, x: f64, y: f64, heading: f64) -> [(f64, f64); 4] {
+        let (sin, cos) = heading.sin_cos();
+        [
+            (self.front, self.side),
+            (self.front, -self.side),
+            (-self.back, -self.side),
+            (-self.back, self.side),
+        ]
+        .map(|(dx, dy)| (x + dx * cos - dy * sin, y + dx * sin + dy * cos))
+    }
+}
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy)]
 pub struct Module<T: SnapshottableType> {
     pub x: T,
     pub y: T,
@@ -78,7 +122,7 @@ impl Module<f64> {
         self.x.hypot(self.y)
     }
 }
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct RobotConfig<T: SnapshottableType> {
     // front left
@@ -140,7 +184,7 @@ impl RobotConfig<f64> {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectFile {
     pub name: String,
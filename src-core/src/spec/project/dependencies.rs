@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    spec::{
+        expression::{expression_message, is_builtin_symbol, Node},
+        Expr,
+    },
+    ChoreoError, ChoreoResult,
+};
+
+use super::Variables;
+
+/// A reference from a variable to a name that is neither a variable, unit nor constant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndefinedReference {
+    /// The variable whose expression contains the reference.
+    pub variable: String,
+    /// The undefined name, e.g. `speed` or `pose.z`.
+    pub name: String,
+}
+
+/// The references between the expressions in [`Variables`].
+///
+/// Expression variables are keyed by their name, and each component of a pose variable
+/// is keyed separately as `name.x`, `name.y` and `name.heading`, matching how expressions
+/// refer to them. Queries that take a variable name also accept a pose name,
+/// which stands for all three of its components.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// For each variable, the variables its expression references.
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    undefined: Vec<UndefinedReference>,
+}
+
+fn collect_references(
+    variables: &Variables,
+    node: &Node,
+    defined: &mut BTreeSet<String>,
+    undefined: &mut Vec<String>,
+) {
+    match node {
+        Node::Number(_) => {}
+        Node::Symbol(name) => {
+            // Variables shadow units and constants of the same name
+            if variables.expressions.contains_key(name) {
+                defined.insert(name.clone());
+            } else if !is_builtin_symbol(name) {
+                undefined.push(name.clone());
+            }
+        }
+        Node::Accessor { object, property } => {
+            let key = format!("{object}.{property}");
+            if variables.poses.contains_key(object)
+                && matches!(property.as_str(), "x" | "y" | "heading")
+            {
+                defined.insert(key);
+            } else {
+                undefined.push(key);
+            }
+        }
+        Node::Negate(inner) => collect_references(variables, inner, defined, undefined),
+        Node::Binary { lhs, rhs, .. } => {
+            collect_references(variables, lhs, defined, undefined);
+            collect_references(variables, rhs, defined, undefined);
+        }
+        Node::Call { args, .. } => {
+            for arg in args {
+                collect_references(variables, arg, defined, undefined);
+            }
+        }
+    }
+}
+
+impl DependencyGraph {
+    /// Build the graph of references between `variables`.
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Expression`] if a variable's expression can't be parsed.
+    pub fn new(variables: &Variables) -> ChoreoResult<Self> {
+        let mut graph = Self::default();
+        let expressions = variables
+            .expressions
+            .iter()
+            .map(|(name, variable)| (name.clone(), &variable.var));
+        let poses = variables.poses.iter().flat_map(|(name, pose)| {
+            [
+                (format!("{name}.x"), &pose.x),
+                (format!("{name}.y"), &pose.y),
+                (format!("{name}.heading"), &pose.heading),
+            ]
+        });
+        for (key, expr) in expressions.chain(poses) {
+            graph.add(variables, key, expr)?;
+        }
+        Ok(graph)
+    }
+
+    fn add(&mut self, variables: &Variables, key: String, expr: &Expr) -> ChoreoResult<()> {
+        let node = Node::parse(&expr.exp).map_err(|e| {
+            ChoreoError::Expression(format!(
+                "In variable {key} ({}): {}",
+                expr.exp,
+                expression_message(&e)
+            ))
+        })?;
+        let mut defined = BTreeSet::new();
+        let mut undefined = Vec::new();
+        collect_references(variables, &node, &mut defined, &mut undefined);
+        self.undefined
+            .extend(undefined.into_iter().map(|name| UndefinedReference {
+                variable: key.clone(),
+                name,
+            }));
+        self.dependencies.insert(key, defined);
+        Ok(())
+    }
+
+    /// The keys of the graph that `name` stands for.
+    fn keys<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.dependencies.keys().filter(move |key| {
+            key.as_str() == name
+                || key
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+
+    /// The variables directly referenced by `name`.
+    #[must_use]
+    pub fn dependencies(&self, name: &str) -> BTreeSet<&str> {
+        self.keys(name)
+            .flat_map(|key| &self.dependencies[key])
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The variables whose expressions directly reference `name`.
+    #[must_use]
+    pub fn dependents(&self, name: &str) -> BTreeSet<&str> {
+        let keys = self.keys(name).collect::<BTreeSet<_>>();
+        self.dependencies
+            .iter()
+            .filter(|(_, dependencies)| dependencies.iter().any(|d| keys.contains(d)))
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    /// Every variable whose value changes if `name` changes,
+    /// whether it references `name` directly or through other variables.
+    #[must_use]
+    pub fn transitive_dependents(&self, name: &str) -> BTreeSet<&str> {
+        let mut out = BTreeSet::new();
+        let mut queue = self.dependents(name).into_iter().collect::<Vec<_>>();
+        while let Some(key) = queue.pop() {
+            if out.insert(key) {
+                queue.extend(self.dependents(key));
+            }
+        }
+        out
+    }
+
+    /// References to names that are neither variables, units nor constants.
+    #[must_use]
+    pub fn undefined_references(&self) -> &[UndefinedReference] {
+        &self.undefined
+    }
+
+    /// The strongly connected components of the graph, in an order where every component
+    /// comes after all the components it depends on.
+    fn components(&self) -> Vec<Vec<&str>> {
+        struct Tarjan<'a> {
+            graph: &'a DependencyGraph,
+            index: BTreeMap<&'a str, usize>,
+            low_link: BTreeMap<&'a str, usize>,
+            stack: Vec<&'a str>,
+            components: Vec<Vec<&'a str>>,
+        }
+
+        impl<'a> Tarjan<'a> {
+            fn visit(&mut self, key: &'a str) {
+                let index = self.index.len();
+                self.index.insert(key, index);
+                self.low_link.insert(key, index);
+                self.stack.push(key);
+                let graph = self.graph;
+                for dependency in &graph.dependencies[key] {
+                    let dependency = dependency.as_str();
+                    if !self.index.contains_key(dependency) {
+                        self.visit(dependency);
+                        let low = self.low_link[key].min(self.low_link[dependency]);
+                        self.low_link.insert(key, low);
+                    } else if self.stack.contains(&dependency) {
+                        let low = self.low_link[key].min(self.index[dependency]);
+                        self.low_link.insert(key, low);
+                    }
+                }
+                if self.low_link[key] == self.index[key] {
+                    let position = self.stack.iter().rposition(|k| *k == key).unwrap_or(0);
+                    let mut component = self.stack.split_off(position);
+                    component.sort_unstable();
+                    self.components.push(component);
+                }
+            }
+        }
+
+        let mut tarjan = Tarjan {
+            graph: self,
+            index: BTreeMap::new(),
+            low_link: BTreeMap::new(),
+            stack: Vec::new(),
+            components: Vec::new(),
+        };
+        for key in self.dependencies.keys() {
+            if !tarjan.index.contains_key(key.as_str()) {
+                tarjan.visit(key);
+            }
+        }
+        tarjan.components
+    }
+
+    /// The groups of variables that reference each other in a cycle,
+    /// including variables that reference themselves.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        self.components()
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.dependencies[component[0]].contains(component[0])
+            })
+            .collect()
+    }
+
+    /// Every variable, ordered so that each comes after the variables it references.
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Variable`] if any variables reference each other in a cycle.
+    pub fn evaluation_order(&self) -> ChoreoResult<Vec<&str>> {
+        let cycles = self.cycles();
+        if !cycles.is_empty() {
+            return Err(ChoreoError::Variable(format!(
+                "Circular references between {}",
+                cycles
+                    .iter()
+                    .map(|cycle| cycle.join(", "))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )));
+        }
+        Ok(self.components().into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::project::{Dimension, PoseVariable, Variable};
+
+    fn variables(expressions: &[(&str, &str)], poses: &[(&str, [&str; 3])]) -> Variables {
+        let expr = |exp: &str| Expr::new(exp, 0.0);
+        Variables {
+            expressions: expressions
+                .iter()
+                .map(|(name, exp)| {
+                    (
+                        (*name).to_string(),
+                        Variable {
+                            dimension: Dimension::Length,
+                            var: expr(exp),
+                        },
+                    )
+                })
+                .collect(),
+            poses: poses
+                .iter()
+                .map(|(name, [x, y, heading])| {
+                    (
+                        (*name).to_string(),
+                        PoseVariable {
+                            x: expr(x),
+                            y: expr(y),
+                            heading: expr(heading),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn order_and_queries() {
+        let graph = DependencyGraph::new(&variables(
+            &[("c", "a + b"), ("b", "2 a"), ("a", "1 m"), ("d", "score.x")],
+            &[("score", ["b", "1 m", "0 deg"])],
+        ))
+        .unwrap();
+        let order = graph.evaluation_order().unwrap();
+        let position = |key| order.iter().position(|k| *k == key).unwrap();
+        assert!(position("a") < position("b"));
+        assert!(position("b") < position("c"));
+        assert!(position("score.x") < position("d"));
+
+        assert_eq!(graph.dependencies("c"), BTreeSet::from(["a", "b"]));
+        assert_eq!(graph.dependents("a"), BTreeSet::from(["b", "c"]));
+        assert_eq!(graph.dependents("score"), BTreeSet::from(["d"]));
+        assert_eq!(
+            graph.transitive_dependents("a"),
+            BTreeSet::from(["b", "c", "d", "score.x"])
+        );
+        assert!(graph.undefined_references().is_empty());
+        assert!(graph.cycles().is_empty());
+    }
+
+    #[test]
+    fn cycles_and_undefined() {
+        let graph = DependencyGraph::new(&variables(
+            &[
+                ("a", "b"),
+                ("b", "c + 1 m"),
+                ("c", "a"),
+                ("d", "d"),
+                ("e", "f + pose.x"),
+            ],
+            &[("pose", ["pose.y", "pose.x", "0 deg"])],
+        ))
+        .unwrap();
+        assert_eq!(
+            graph.cycles(),
+            vec![vec!["a", "b", "c"], vec!["d"], vec!["pose.x", "pose.y"]]
+        );
+        assert!(graph.evaluation_order().is_err());
+        assert_eq!(
+            graph.undefined_references(),
+            &[UndefinedReference {
+                variable: "e".to_string(),
+                name: "f".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! JSON Schema documents for the `.chor` and `.traj` file formats.
+//!
+//! The schemas are derived from the serde types in [`project`](super::project) and
+//! [`trajectory`](super::trajectory), and pin the `version` field to the current
+//! [`PROJECT_SCHEMA_VERSION`] and [`TRAJ_SCHEMA_VERSION`]. Files from older versions
+//! should be upgraded before they are validated.
+
+use std::fmt::{self, Display};
+
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+
+use super::{
+    project::ProjectFile, trajectory::TrajectoryFile, PROJECT_SCHEMA_VERSION, TRAJ_SCHEMA_VERSION,
+};
+
+/// A location in a file that doesn't match its schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaError {
+    /// The JSON path of the offending value, e.g. `params.waypoints.0.x.val`.
+    ///
+    /// Empty for the root of the file.
+    pub path: String,
+    /// A human readable description of the problem.
+    pub message: String,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+fn schema_for<T: JsonSchema>(title: &str, version: u32) -> JsonValue {
+    let schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>();
+    let mut schema = serde_json::to_value(schema).unwrap_or_default();
+    schema["title"] = json!(title);
+    schema["properties"]["version"] = json!({
+        "description": "The version of the file format this schema describes.",
+        "const": version
+    });
+    schema
+}
+
+/// The JSON Schema for `.chor` files of the current [`PROJECT_SCHEMA_VERSION`].
+#[must_use]
+pub fn project_schema() -> JsonValue {
+    schema_for::<ProjectFile>(
+        &format!("Choreo project file, version {PROJECT_SCHEMA_VERSION}"),
+        PROJECT_SCHEMA_VERSION,
+    )
+}
+
+/// The JSON Schema for `.traj` files of the current [`TRAJ_SCHEMA_VERSION`].
+#[must_use]
+pub fn trajectory_schema() -> JsonValue {
+    schema_for::<TrajectoryFile>(
+        &format!("Choreo trajectory file, version {TRAJ_SCHEMA_VERSION}"),
+        TRAJ_SCHEMA_VERSION,
+    )
+}
+
+/// The file names and contents of the current schemas,
+/// for example `project.v1.schema.json`.
+#[must_use]
+pub fn schema_files() -> Vec<(String, JsonValue)> {
+    vec![
+        (
+            format!("project.v{PROJECT_SCHEMA_VERSION}.schema.json"),
+            project_schema(),
+        ),
+        (
+            format!("trajectory.v{TRAJ_SCHEMA_VERSION}.schema.json"),
+            trajectory_schema(),
+        ),
+    ]
+}
+
+/// Check the contents of a `.chor` file against [`project_schema`].
+///
+/// Returns every mismatch found, or an empty list if the file is valid.
+#[must_use]
+pub fn validate_project(json: &JsonValue) -> Vec<SchemaError> {
+    validate(&project_schema(), json)
+}
+
+/// Check the contents of a `.traj` file against [`trajectory_schema`].
+///
+/// Returns every mismatch found, or an empty list if the file is valid.
+#[must_use]
+pub fn validate_trajectory(json: &JsonValue) -> Vec<SchemaError> {
+    validate(&trajectory_schema(), json)
+}
+
+fn validate(root: &JsonValue, json: &JsonValue) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    Validator { root }.check(root, json, "", &mut errors);
+    errors
+}
+
+fn join(path: &str, key: impl Display) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn type_name(json: &JsonValue) -> &'static str {
+    match json {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(n) if n.is_u64() || n.is_i64() => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+fn has_type(json: &JsonValue, name: &str) -> bool {
+    match name {
+        "number" => json.is_number(),
+        "integer" => {
+            json.is_u64() || json.is_i64() || json.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => type_name(json) == name,
+    }
+}
+
+/// The depth of the deepest error, used to pick the alternative of a
+/// `oneOf`/`anyOf` that got furthest into the value.
+fn depth(errors: &[SchemaError]) -> usize {
+    errors
+        .iter()
+        .map(|e| e.path.split('.').filter(|s| !s.is_empty()).count())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Validates the subset of JSON Schema draft 7 that `schemars` produces for the spec types.
+struct Validator<'a> {
+    root: &'a JsonValue,
+}
+
+impl Validator<'_> {
+    fn resolve<'s>(&'s self, schema: &'s JsonValue) -> &'s JsonValue {
+        match schema.get("$ref").and_then(JsonValue::as_str) {
+            Some(reference) => reference
+                .strip_prefix("#/")
+                .map(|pointer| format!("/{pointer}"))
+                .and_then(|pointer| self.root.pointer(&pointer))
+                .unwrap_or(&JsonValue::Null),
+            None => schema,
+        }
+    }
+
+    /// Whether `json` has the tag of an enum variant `schema`, if it is one.
+    ///
+    /// Serde's internally and adjacently tagged enums show up as properties
+    /// that only allow a single value.
+    fn tag_matches(&self, schema: &JsonValue, json: &JsonValue) -> bool {
+        let Some(properties) = self
+            .resolve(schema)
+            .get("properties")
+            .and_then(JsonValue::as_object)
+        else {
+            return true;
+        };
+        properties.iter().all(|(key, property)| {
+            match (
+                property.get("enum").and_then(JsonValue::as_array),
+                json.get(key),
+            ) {
+                (Some(tags), Some(value)) if tags.len() == 1 => &tags[0] == value,
+                _ => true,
+            }
+        })
+    }
+
+    fn check(
+        &self,
+        schema: &JsonValue,
+        json: &JsonValue,
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        let schema = self.resolve(schema);
+        let Some(schema) = schema.as_object() else {
+            return;
+        };
+        let mut error = |message: String| {
+            errors.push(SchemaError {
+                path: path.to_string(),
+                message,
+            });
+        };
+
+        if let Some(types) = schema.get("type") {
+            let types = match types {
+                JsonValue::Array(types) => types.iter().filter_map(JsonValue::as_str).collect(),
+                other => other.as_str().into_iter().collect::<Vec<_>>(),
+            };
+            if !types.iter().any(|t| has_type(json, t)) {
+                error(format!(
+                    "expected {}, found {}",
+                    types.join(" or "),
+                    type_name(json)
+                ));
+                return;
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if json != expected {
+                error(format!("expected {expected}, found {json}"));
+            }
+        }
+        if let Some(JsonValue::Array(values)) = schema.get("enum") {
+            if !values.contains(json) {
+                let values = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                error(format!(
+                    "expected one of {}, found {json}",
+                    values.join(", ")
+                ));
+            }
+        }
+        if let (Some(minimum), Some(value)) = (
+            schema.get("minimum").and_then(JsonValue::as_f64),
+            json.as_f64(),
+        ) {
+            if value < minimum {
+                error(format!("expected at least {minimum}, found {value}"));
+            }
+        }
+        if let JsonValue::Object(object) = json {
+            self.check_object(schema, object, path, errors);
+        }
+        if let JsonValue::Array(items) = json {
+            self.check_array(schema, items, path, errors);
+        }
+        if let Some(JsonValue::Array(all)) = schema.get("allOf") {
+            for subschema in all {
+                self.check(subschema, json, path, errors);
+            }
+        }
+        for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+            if let Some(JsonValue::Array(alternatives)) = schema.get(keyword) {
+                self.check_alternatives(alternatives, exactly_one, json, path, errors);
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, JsonValue>,
+        object: &Map<String, JsonValue>,
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if let Some(JsonValue::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(JsonValue::as_str) {
+                if !object.contains_key(key) {
+                    errors.push(SchemaError {
+                        path: path.to_string(),
+                        message: format!("missing required property \"{key}\""),
+                    });
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(JsonValue::as_object);
+        for (key, value) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(subschema) => self.check(subschema, value, &join(path, key), errors),
+                None if schema.get("additionalProperties") == Some(&JsonValue::Bool(false)) => {
+                    errors.push(SchemaError {
+                        path: join(path, key),
+                        message: "unexpected property".to_string(),
+                    });
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, JsonValue>,
+        items: &[JsonValue],
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        let length = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(JsonValue::as_u64) {
+            if length < min {
+                errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!("expected at least {min} items, found {length}"),
+                });
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(JsonValue::as_u64) {
+            if length > max {
+                errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!("expected at most {max} items, found {length}"),
+                });
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                self.check(item_schema, item, &join(path, i), errors);
+            }
+        }
+    }
+
+    fn check_alternatives(
+        &self,
+        alternatives: &[JsonValue],
+        exactly_one: bool,
+        json: &JsonValue,
+        path: &str,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        let results = alternatives
+            .iter()
+            .map(|alternative| {
+                let mut errors = Vec::new();
+                self.check(alternative, json, path, &mut errors);
+                (self.tag_matches(alternative, json), errors)
+            })
+            .collect::<Vec<_>>();
+        let matches = results.iter().filter(|(_, e)| e.is_empty()).count();
+        if matches == 1 || (matches > 1 && !exactly_one) {
+            return;
+        }
+        if matches > 1 {
+            errors.push(SchemaError {
+                path: path.to_string(),
+                message: format!("matches {matches} alternatives, expected exactly one"),
+            });
+            return;
+        }
+        // Report the alternative that the value most likely meant to match,
+        // preferring the variant named by its tag.
+        let any_tag_matches = results.iter().any(|(tag_matches, _)| *tag_matches);
+        if let Some((_, best)) = results
+            .into_iter()
+            .filter(|(tag_matches, _)| *tag_matches || !any_tag_matches)
+            .max_by(|(_, a), (_, b)| depth(a).cmp(&depth(b)).then(b.len().cmp(&a.len())))
+        {
+            errors.extend(best);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::upgraders::{upgrade_project_file, upgrade_traj_file};
+
+    #[test]
+    fn test_jsons_are_valid() {
+        for project in [
+            include_str!("../../../test-jsons/project/1/swerve.chor"),
+            include_str!("../../../test-jsons/project/1/differential.chor"),
+        ] {
+            let project = upgrade_project_file(serde_json::from_str(project).unwrap()).unwrap();
+            assert_eq!(validate_project(&project), vec![]);
+        }
+        for trajectory in [
+            include_str!("../../../test-jsons/trajectory/1/swerve.traj"),
+            include_str!("../../../test-jsons/trajectory/1/differential.traj"),
+        ] {
+            let trajectory = upgrade_traj_file(serde_json::from_str(trajectory).unwrap()).unwrap();
+            assert_eq!(validate_trajectory(&trajectory), vec![]);
+        }
+    }
+
+    #[test]
+    fn reports_paths() {
+        let mut trajectory = upgrade_traj_file(
+            serde_json::from_str(include_str!("../../../test-jsons/trajectory/1/swerve.traj"))
+                .unwrap(),
+        )
+        .unwrap();
+        trajectory["version"] = json!(0);
+        trajectory["params"]["waypoints"][1]["x"]["val"] = json!("1 m");
+        trajectory["params"]["constraints"][0]["from"] = json!("middle");
+        // A MaxVelocity constraint
+        trajectory["params"]["constraints"][3]["data"]["props"] = json!({});
+        trajectory["trajectory"]["samples"][2]["fx"] = json!([0.0, 0.0]);
+
+        let errors = validate_trajectory(&trajectory);
+        let mut paths = errors.iter().map(|e| e.path.as_str()).collect::<Vec<_>>();
+        paths.sort_unstable();
+        assert_eq!(
+            paths,
+            vec![
+                "params.constraints.0.from",
+                "params.constraints.3.data.props",
+                "params.waypoints.1.x.val",
+                "trajectory.samples.2.fx",
+                "version",
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Summary statistics of a generated trajectory.
+
+use serde::{Deserialize, Serialize};
+
+use super::trajectory::{Sample, Trajectory};
+
+/// A summary of a generated trajectory, for either drive type.
+///
+/// For differential drives, speeds and accelerations are those of the center
+/// of the robot, and module forces are the forces on each side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrajectoryStats {
+    /// Units: seconds
+    pub total_time: f64,
+    /// The distance travelled along the ground, between the samples.
+    ///
+    /// Units: meters
+    pub path_length: f64,
+    /// Units: meters / second
+    pub max_speed: f64,
+    /// Units: meters / second²
+    pub max_acceleration: f64,
+    /// Units: radians / second
+    pub max_angular_velocity: f64,
+    /// The largest force on any one module.
+    ///
+    /// Units: newtons
+    pub peak_module_force: f64,
+    /// The duration of each split section, in order.
+    ///
+    /// Units: seconds
+    pub split_durations: Vec<f64>,
+}
+
+impl Sample {
+    /// The speed, acceleration, angular velocity and largest module force of the sample.
+    fn magnitudes(&self) -> [f64; 4] {
+        match *self {
+            Sample::Swerve {
+                vx,
+                vy,
+                omega,
+                ax,
+                ay,
+                fx,
+                fy,
+                ..
+            } => [
+                vx.hypot(vy),
+                ax.hypot(ay),
+                omega.abs(),
+                fx.iter()
+                    .zip(fy)
+                    .map(|(fx, fy)| fx.hypot(fy))
+                    .fold(0.0, f64::max),
+            ],
+            Sample::DifferentialDrive {
+                vl,
+                vr,
+                omega,
+                al,
+                ar,
+                fl,
+                fr,
+                ..
+            } => [
+                ((vl + vr) / 2.0).abs(),
+                ((al + ar) / 2.0).abs(),
+                omega.abs(),
+                fl.abs().max(fr.abs()),
+            ],
+        }
+    }
+}
+
+impl Trajectory {
+    /// A summary of the trajectory, which is all zeroes if it has no samples.
+    #[must_use]
+    pub fn stats(&self) -> TrajectoryStats {
+        let [max_speed, max_acceleration, max_angular_velocity, peak_module_force] = self
+            .samples
+            .iter()
+            .map(Sample::magnitudes)
+            .fold([0.0; 4], |max, sample| {
+                [0, 1, 2, 3].map(|i| f64::max(max[i], sample[i]))
+            });
+        let path_length = self
+            .samples
+            .windows(2)
+            .map(|pair| {
+                let (a, b) = (pair[0].pose(), pair[1].pose());
+                (b.x - a.x).hypot(b.y - a.y)
+            })
+            .sum();
+
+        let mut starts = self
+            .splits
+            .iter()
+            .copied()
+            .filter(|i| *i + 1 < self.samples.len())
+            .collect::<Vec<_>>();
+        if !self.samples.is_empty() {
+            starts.push(0);
+        }
+        starts.sort_unstable();
+        starts.dedup();
+        let split_durations = starts
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let end = starts.get(i + 1).copied().unwrap_or(self.samples.len() - 1);
+                self.samples[end].t() - self.samples[*start].t()
+            })
+            .collect();
+
+        TrajectoryStats {
+            total_time: self.total_time(),
+            path_length,
+            max_speed,
+            max_acceleration,
+            max_angular_velocity,
+            peak_module_force,
+            split_durations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::trajectory::DriveType;
+
+    fn differential(t: f64, x: f64, vl: f64, vr: f64, fl: f64) -> Sample {
+        Sample::DifferentialDrive {
+            t,
+            x,
+            y: 0.0,
+            heading: 0.0,
+            vl,
+            vr,
+            omega: (vr - vl) / 2.0,
+            al: 1.0,
+            ar: 3.0,
+            fl,
+            fr: 0.0,
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let trajectory = Trajectory {
+            sample_type: Some(DriveType::Differential),
+            waypoints: vec![0.0, 1.0, 3.0],
+            samples: vec![
+                differential(0.0, 0.0, 0.0, 0.0, 0.0),
+                differential(1.0, 1.5, -1.0, 3.0, -4.0),
+                differential(3.0, 4.0, 0.0, 0.0, 2.0),
+            ],
+            splits: vec![0, 1],
+        };
+        assert_eq!(
+            trajectory.stats(),
+            TrajectoryStats {
+                total_time: 3.0,
+                path_length: 4.0,
+                max_speed: 1.0,
+                max_acceleration: 2.0,
+                max_angular_velocity: 2.0,
+                peak_module_force: 4.0,
+                split_durations: vec![1.0, 2.0],
+            }
+        );
+
+        let empty = Trajectory {
+            sample_type: None,
+            waypoints: vec![],
+            samples: vec![],
+            splits: vec![],
+        };
+        assert_eq!(empty.stats().split_durations, Vec::<f64>::new());
+        assert_eq!(empty.stats().path_length, 0.0);
+    }
+}
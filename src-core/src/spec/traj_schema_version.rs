@@ -1,2 +1,2 @@
 // Auto-generated by update_traj_schema.py
-pub const TRAJ_SCHEMA_VERSION: u32 = 1;
+pub const TRAJ_SCHEMA_VERSION: u32 = 2;
@@ -1,9 +1,30 @@
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use trajoptlib::{DifferentialTrajectorySample, SwerveTrajectorySample};
 
-use super::{upgraders::upgrade_traj_file, Expr, SnapshottableType};
+use super::{project::Variables, upgraders::upgrade_traj_file, Expr, SnapshottableType};
+use crate::{ChoreoError, ChoreoResult};
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+mod binary;
+mod csv;
+mod markers;
+mod reverse;
+mod sampling;
+mod split;
+mod stitch;
+mod wpilib;
+
+pub use binary::{BinaryTrajectory, Precision};
+pub use csv::CsvColumns;
+pub use markers::MarkerTime;
+pub use stitch::StitchTolerance;
+pub use wpilib::{WpilibPose, WpilibRotation, WpilibState, WpilibTranslation};
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 /// A waypoint parameter.
 pub struct Waypoint<T: SnapshottableType> {
@@ -19,6 +40,12 @@ pub struct Waypoint<T: SnapshottableType> {
     ///
     /// Units: radians
     pub heading: T,
+    /// The name of the pose variable this waypoint is bound to.
+    ///
+    /// When set, `x`, `y` and `heading` follow the pose of that name in the project's
+    /// [`Variables`], see [`TrajectoryFile::resolve_pose_links`].
+    #[serde(default)]
+    pub pose: Option<String>,
     /// The number of control intervals to use between this waypoint and the next.
     pub intervals: usize,
     /// Whether to split the trajectory at this waypoint.
@@ -42,6 +69,7 @@ impl<T: SnapshottableType> Waypoint<T> {
             x: self.x.snapshot(),
             y: self.y.snapshot(),
             heading: self.heading.snapshot(),
+            pose: self.pose.clone(),
             intervals: self.intervals,
             split: self.split,
             fix_translation: self.fix_translation,
@@ -64,6 +92,29 @@ pub enum WaypointID {
     #[serde(untagged)]
     Idx(usize),
 }
+// The derive doesn't support untagged variants, so `Idx` would be given an `idx` key.
+impl JsonSchema for WaypointID {
+    fn schema_name() -> String {
+        "WaypointID".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let names = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(vec!["first".into(), "last".into()]),
+            ..Default::default()
+        };
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                one_of: Some(vec![names.into(), gen.subschema_for::<usize>()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl WaypointID {
     /// TODO
     #[must_use]
@@ -106,7 +157,7 @@ pub enum ConstraintScope {
 }
 
 /// A constraint on the robot's motion.
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq)]
 #[serde(tag = "type", content = "props")]
 pub enum ConstraintData<T: SnapshottableType> {
     /// A constraint on the maximum velocity.
@@ -211,7 +262,7 @@ impl<T: SnapshottableType> ConstraintData<T> {
 }
 
 /// A constraint on the robot's motion and where it applies.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct Constraint<T: SnapshottableType> {
     /// The waypoint the constraint starts at.
     pub from: WaypointID,
@@ -252,7 +303,7 @@ pub struct ConstraintIDX<T: SnapshottableType> {
 
 /// A sample of the robot's state at a point in time during the trajectory.
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(untagged)]
 pub enum Sample {
     /// A sample for a swerve drive.
@@ -353,7 +404,7 @@ impl From<DifferentialTrajectorySample> for Sample {
 }
 
 /// The type of samples in a trajectory.
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 pub enum DriveType {
     /// The variant for [`Sample::Swerve`].
     #[default]
@@ -363,7 +414,7 @@ pub enum DriveType {
 }
 
 /// The parameters used for generating a trajectory.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Parameters<T: SnapshottableType> {
     /// The waypoints the robot will pass through or use for initial guess.
@@ -391,7 +442,7 @@ impl<T: SnapshottableType> Parameters<T> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 /// The trajectory the robot will follow.
 pub struct Trajectory {
@@ -410,7 +461,7 @@ pub struct Trajectory {
 }
 
 /// A structure representing a `.traj` file.
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TrajectoryFile {
     /// The name of the `.traj` file.
@@ -442,6 +493,40 @@ impl TrajectoryFile {
         serde_json::from_value(val).map_err(Into::into)
     }
 
+    /// Set the `x`, `y` and `heading` of every waypoint bound to a pose variable
+    /// to reference that pose in `variables`.
+    ///
+    /// Returns `true` if any waypoint changed. A trajectory whose waypoints moved
+    /// no longer matches its snapshot, so it will not be [`up_to_date`](Self::up_to_date).
+    ///
+    /// # Errors
+    /// - [`ChoreoError::Expression`] if a waypoint is bound to a pose that doesn't exist.
+    pub fn resolve_pose_links(&mut self, variables: &Variables) -> ChoreoResult<bool> {
+        let mut changed = false;
+        for (i, waypoint) in self.params.waypoints.iter_mut().enumerate() {
+            let Some(name) = &waypoint.pose else {
+                continue;
+            };
+            let pose = variables.poses.get(name).ok_or_else(|| {
+                ChoreoError::Expression(format!(
+                    "Waypoint {i} is bound to undefined pose variable \"{name}\""
+                ))
+            })?;
+            for (field, expr, value) in [
+                ("x", &mut waypoint.x, pose.x.val),
+                ("y", &mut waypoint.y, pose.y.val),
+                ("heading", &mut waypoint.heading, pose.heading.val),
+            ] {
+                let exp = format!("{name}.{field}");
+                if expr.exp != exp || expr.val != value {
+                    *expr = Expr::new(&exp, value);
+                    changed = true;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
     pub fn up_to_date(&self) -> bool {
         // Can't use is_some_and due to its move semantics.
         if let Some(snap) = &self.snapshot {
@@ -452,15 +537,15 @@ impl TrajectoryFile {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EventMarkerData {
-    pub target: Option<usize>,
+    pub target: Option<WaypointID>,
     pub target_timestamp: Option<f64>,
     pub offset: Expr,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EventMarker {
     pub name: String,
@@ -468,11 +553,11 @@ pub struct EventMarker {
     pub event: Option<PplibCommand>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "type", content = "data")]
 pub enum PplibCommand {
     Named {
-        name: String,
+        name: Option<String>,
     },
     #[serde(rename_all = "camelCase")]
     Wait {
@@ -503,6 +588,7 @@ mod tests {
                 x: Expr::fill_in_value(0.0, "m"),
                 y: Expr::fill_in_value(0.0, "m"),
                 heading: Expr::fill_in_value(0.0, "rad"),
+                pose: None,
                 intervals: 20,
                 split: false,
                 fix_translation: false,
@@ -541,4 +627,33 @@ mod tests {
         assert!(deser_trajectory.is_ok_and(|t| t.up_to_date()));
         Ok(())
     }
+
+    #[test]
+    fn pose_links() {
+        use crate::spec::project::{PoseVariable, ProjectFile};
+
+        let mut variables = ProjectFile::default().variables;
+        variables.poses.insert(
+            "score".to_string(),
+            PoseVariable {
+                x: Expr::new("2 m", 2.0),
+                y: Expr::new("3 m", 3.0),
+                heading: Expr::new("90 deg", std::f64::consts::FRAC_PI_2),
+            },
+        );
+        let mut trajectory = test_trajectory();
+        assert!(!trajectory.resolve_pose_links(&variables).unwrap());
+
+        trajectory.params.waypoints[0].pose = Some("score".to_string());
+        assert!(trajectory.resolve_pose_links(&variables).unwrap());
+        let waypoint = &trajectory.params.waypoints[0];
+        assert_eq!(waypoint.x.exp, "score.x");
+        assert_eq!(waypoint.y.val, 3.0);
+        assert_eq!(waypoint.heading.val, std::f64::consts::FRAC_PI_2);
+        assert!(!trajectory.up_to_date());
+        assert!(!trajectory.resolve_pose_links(&variables).unwrap());
+
+        trajectory.params.waypoints[0].pose = Some("missing".to_string());
+        assert!(trajectory.resolve_pose_links(&variables).is_err());
+    }
 }
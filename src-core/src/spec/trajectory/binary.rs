@@ -0,0 +1,493 @@
+use std::io::{self, Read, Write};
+
+use crate::{spec::Expr, ChoreoResult};
+
+use super::{
+    DriveType, EventMarker, EventMarkerData, PplibCommand, Sample, Trajectory, TrajectoryFile,
+    WaypointID,
+};
+
+/// The bytes every binary trajectory starts with.
+const MAGIC: &[u8; 4] = b"CHOR";
+
+/// The precision of the numbers in a binary trajectory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// 4 byte floats, half the size but with about 7 significant digits.
+    F32,
+    /// 8 byte floats, as precise as the JSON files.
+    #[default]
+    F64,
+}
+
+/// A trajectory and its event markers, in a compact binary format for robot code to load.
+///
+/// All values are little endian. The file starts with a header of
+/// - the magic bytes `CHOR`
+/// - the format version as a `u16`
+/// - the precision of the numbers as a `u8`, 0 for `f32` and 1 for `f64`
+/// - the sample type as a `u8`, 0 for none, 1 for swerve and 2 for differential
+///
+/// followed by the name, waypoint times, splits, samples and event markers.
+/// Lists are prefixed with their length as a `u32`, strings are UTF-8 lists of bytes,
+/// and each sample is its fields in the order of the JSON format, with module forces
+/// flattened. Event marker commands are stored as JSON, or an empty string if there is none.
+#[derive(Debug, Clone)]
+pub struct BinaryTrajectory {
+    /// The name of the trajectory.
+    pub name: String,
+    /// The generated trajectory.
+    pub trajectory: Trajectory,
+    /// The event markers of the trajectory.
+    pub events: Vec<EventMarker>,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Writer<W: Write> {
+    inner: W,
+    precision: Precision,
+}
+
+impl<W: Write> Writer<W> {
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.inner.write_all(&[value])
+    }
+
+    fn u32(&mut self, value: usize) -> io::Result<()> {
+        let value = u32::try_from(value).map_err(|e| invalid(e.to_string()))?;
+        self.inner.write_all(&value.to_le_bytes())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn float(&mut self, value: f64) -> io::Result<()> {
+        match self.precision {
+            Precision::F32 => self.inner.write_all(&(value as f32).to_le_bytes()),
+            Precision::F64 => self.inner.write_all(&value.to_le_bytes()),
+        }
+    }
+
+    fn string(&mut self, value: &str) -> io::Result<()> {
+        self.u32(value.len())?;
+        self.inner.write_all(value.as_bytes())
+    }
+}
+
+struct Reader<R: Read> {
+    inner: R,
+    precision: Precision,
+}
+
+impl<R: Read> Reader<R> {
+    /// Read the header up to the precision, which the rest is read with.
+    fn new(inner: R) -> io::Result<Self> {
+        let mut reader = Reader {
+            inner,
+            precision: Precision::F64,
+        };
+        if &reader.bytes::<4>()? != MAGIC {
+            return Err(invalid("Not a binary trajectory".to_string()));
+        }
+        let version = u16::from_le_bytes(reader.bytes()?);
+        if version > BinaryTrajectory::VERSION {
+            return Err(invalid(format!(
+                "Binary trajectory version {version} is newer than {}",
+                BinaryTrajectory::VERSION
+            )));
+        }
+        reader.precision = match reader.u8()? {
+            0 => Precision::F32,
+            1 => Precision::F64,
+            precision => return Err(invalid(format!("Unknown precision {precision}"))),
+        };
+        Ok(reader)
+    }
+
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.inner.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<usize> {
+        usize::try_from(u32::from_le_bytes(self.bytes()?)).map_err(|e| invalid(e.to_string()))
+    }
+
+    fn float(&mut self) -> io::Result<f64> {
+        Ok(match self.precision {
+            Precision::F32 => f64::from(f32::from_le_bytes(self.bytes()?)),
+            Precision::F64 => f64::from_le_bytes(self.bytes()?),
+        })
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let len = self.u32()?;
+        // The length isn't trusted with an allocation until the bytes are there
+        let mut bytes = Vec::new();
+        (&mut self.inner).take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(bytes).map_err(|e| invalid(e.to_string()))
+    }
+
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> io::Result<T>) -> io::Result<Vec<T>> {
+        (0..self.u32()?).map(|_| item(self)).collect()
+    }
+}
+
+impl BinaryTrajectory {
+    /// The extension of binary trajectory files.
+    pub const EXTENSION: &'static str = "btraj";
+    /// The version of the binary format written by [`BinaryTrajectory::write`].
+    pub const VERSION: u16 = 1;
+
+    /// Write the trajectory in the binary format, with numbers of the given precision.
+    ///
+    /// Fails if the samples are not all of the sample type of the trajectory.
+    pub fn write(&self, writer: impl Write, precision: Precision) -> ChoreoResult<()> {
+        let mut writer = Writer {
+            inner: writer,
+            precision,
+        };
+        let sample_type = self
+            .trajectory
+            .sample_type
+            .or(match self.trajectory.samples.first() {
+                Some(Sample::Swerve { .. }) => Some(DriveType::Swerve),
+                Some(Sample::DifferentialDrive { .. }) => Some(DriveType::Differential),
+                None => None,
+            });
+        writer.inner.write_all(MAGIC)?;
+        writer.inner.write_all(&Self::VERSION.to_le_bytes())?;
+        writer.u8(match precision {
+            Precision::F32 => 0,
+            Precision::F64 => 1,
+        })?;
+        writer.u8(match sample_type {
+            None => 0,
+            Some(DriveType::Swerve) => 1,
+            Some(DriveType::Differential) => 2,
+        })?;
+        writer.string(&self.name)?;
+
+        writer.u32(self.trajectory.waypoints.len())?;
+        for t in &self.trajectory.waypoints {
+            writer.float(*t)?;
+        }
+        writer.u32(self.trajectory.splits.len())?;
+        for split in &self.trajectory.splits {
+            writer.u32(*split)?;
+        }
+        writer.u32(self.trajectory.samples.len())?;
+        for sample in &self.trajectory.samples {
+            let fields = match (*sample, sample_type) {
+                (
+                    Sample::Swerve {
+                        t,
+                        x,
+                        y,
+                        heading,
+                        vx,
+                        vy,
+                        omega,
+                        ax,
+                        ay,
+                        alpha,
+                        fx,
+                        fy,
+                    },
+                    Some(DriveType::Swerve),
+                ) => [t, x, y, heading, vx, vy, omega, ax, ay, alpha]
+                    .into_iter()
+                    .chain(fx)
+                    .chain(fy)
+                    .collect::<Vec<_>>(),
+                (
+                    Sample::DifferentialDrive {
+                        t,
+                        x,
+                        y,
+                        heading,
+                        vl,
+                        vr,
+                        omega,
+                        al,
+                        ar,
+                        fl,
+                        fr,
+                    },
+                    Some(DriveType::Differential),
+                ) => vec![t, x, y, heading, vl, vr, omega, al, ar, fl, fr],
+                _ => {
+                    return Err(invalid(format!(
+                        "{} has samples of more than one drive type",
+                        self.name
+                    ))
+                    .into())
+                }
+            };
+            for field in fields {
+                writer.float(field)?;
+            }
+        }
+
+        writer.u32(self.events.len())?;
+        for marker in &self.events {
+            writer.string(&marker.name)?;
+            match marker.from.target {
+                None => writer.u8(0)?,
+                Some(WaypointID::First) => writer.u8(1)?,
+                Some(WaypointID::Last) => writer.u8(2)?,
+                Some(WaypointID::Idx(i)) => {
+                    writer.u8(3)?;
+                    writer.u32(i)?;
+                }
+            }
+            match marker.from.target_timestamp {
+                None => writer.u8(0)?,
+                Some(t) => {
+                    writer.u8(1)?;
+                    writer.float(t)?;
+                }
+            }
+            writer.string(&marker.from.offset.exp)?;
+            writer.float(marker.from.offset.val)?;
+            match &marker.event {
+                None => writer.string("")?,
+                Some(command) => writer.string(&serde_json::to_string(command)?)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a trajectory in the binary format.
+    ///
+    /// Fails if the data isn't a binary trajectory, or is from a newer version of the format.
+    pub fn read(reader: impl Read) -> ChoreoResult<BinaryTrajectory> {
+        let mut reader = Reader::new(reader)?;
+        let sample_type = match reader.u8()? {
+            0 => None,
+            1 => Some(DriveType::Swerve),
+            2 => Some(DriveType::Differential),
+            sample_type => return Err(invalid(format!("Unknown sample type {sample_type}")).into()),
+        };
+        let name = reader.string()?;
+
+        let waypoints = reader.list(Reader::float)?;
+        let splits = reader.list(Reader::u32)?;
+        let samples = reader.list(|reader| {
+            let mut field = || reader.float();
+            Ok(match sample_type {
+                Some(DriveType::Swerve) => Sample::Swerve {
+                    t: field()?,
+                    x: field()?,
+                    y: field()?,
+                    heading: field()?,
+                    vx: field()?,
+                    vy: field()?,
+                    omega: field()?,
+                    ax: field()?,
+                    ay: field()?,
+                    alpha: field()?,
+                    fx: [field()?, field()?, field()?, field()?],
+                    fy: [field()?, field()?, field()?, field()?],
+                },
+                Some(DriveType::Differential) => Sample::DifferentialDrive {
+                    t: field()?,
+                    x: field()?,
+                    y: field()?,
+                    heading: field()?,
+                    vl: field()?,
+                    vr: field()?,
+                    omega: field()?,
+                    al: field()?,
+                    ar: field()?,
+                    fl: field()?,
+                    fr: field()?,
+                },
+                None => return Err(invalid("Samples without a sample type".to_string())),
+            })
+        })?;
+
+        let events = reader.list(|reader| {
+            let name = reader.string()?;
+            let target = match reader.u8()? {
+                0 => None,
+                1 => Some(WaypointID::First),
+                2 => Some(WaypointID::Last),
+                3 => Some(WaypointID::Idx(reader.u32()?)),
+                target => return Err(invalid(format!("Unknown marker target {target}"))),
+            };
+            let target_timestamp = match reader.u8()? {
+                0 => None,
+                1 => Some(reader.float()?),
+                flag => return Err(invalid(format!("Unknown marker timestamp flag {flag}"))),
+            };
+            let exp = reader.string()?;
+            let offset = Expr::new(&exp, reader.float()?);
+            let command = reader.string()?;
+            let event = if command.is_empty() {
+                None
+            } else {
+                Some(
+                    serde_json::from_str::<PplibCommand>(&command)
+                        .map_err(|e| invalid(e.to_string()))?,
+                )
+            };
+            Ok(EventMarker {
+                name,
+                from: EventMarkerData {
+                    target,
+                    target_timestamp,
+                    offset,
+                },
+                event,
+            })
+        })?;
+
+        Ok(BinaryTrajectory {
+            name,
+            trajectory: Trajectory {
+                sample_type,
+                waypoints,
+                samples,
+                splits,
+            },
+            events,
+        })
+    }
+
+    /// The trajectory in the binary format, see [`BinaryTrajectory::write`].
+    pub fn to_bytes(&self, precision: Precision) -> ChoreoResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes, precision)?;
+        Ok(bytes)
+    }
+
+    /// The precision of a trajectory in the binary format, read from its header.
+    pub fn precision(reader: impl Read) -> ChoreoResult<Precision> {
+        Ok(Reader::new(reader)?.precision)
+    }
+
+    /// Read a trajectory from bytes in the binary format, see [`BinaryTrajectory::read`].
+    pub fn from_bytes(bytes: &[u8]) -> ChoreoResult<BinaryTrajectory> {
+        Self::read(bytes)
+    }
+}
+
+impl From<&TrajectoryFile> for BinaryTrajectory {
+    fn from(file: &TrajectoryFile) -> Self {
+        Self {
+            name: file.name.clone(),
+            trajectory: file.trajectory.clone(),
+            events: file.events.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::fixtures::swerve_trajectory;
+
+    #[test]
+    fn round_trip() {
+        let mut file = swerve_trajectory();
+        file.events.push(EventMarker {
+            name: "intake".to_string(),
+            from: EventMarkerData {
+                target: Some(WaypointID::Idx(1)),
+                target_timestamp: Some(1.5),
+                offset: Expr::new("0.25 s", 0.25),
+            },
+            event: Some(PplibCommand::Named {
+                name: Some("intake".to_string()),
+            }),
+        });
+        let binary = BinaryTrajectory::from(&file);
+
+        let bytes = binary.to_bytes(Precision::F64).unwrap();
+        assert!(bytes.len() < serde_json::to_string(&file).unwrap().len() / 2);
+        let read = BinaryTrajectory::from_bytes(&bytes).unwrap();
+        assert_eq!(read.name, file.name);
+        assert_eq!(read.trajectory.sample_type, file.trajectory.sample_type);
+        assert_eq!(read.trajectory.waypoints, file.trajectory.waypoints);
+        assert_eq!(read.trajectory.splits, file.trajectory.splits);
+        assert!(read
+            .trajectory
+            .samples
+            .iter()
+            .zip(&file.trajectory.samples)
+            .all(|(read, original)| serde_json::to_value(read).unwrap()
+                == serde_json::to_value(original).unwrap()));
+        let marker = read.events.last().unwrap();
+        assert_eq!(marker.from.target, Some(WaypointID::Idx(1)));
+        assert_eq!(marker.from.offset.exp, "0.25 s");
+        assert!(matches!(marker.event, Some(PplibCommand::Named { .. })));
+
+        let small = binary.to_bytes(Precision::F32).unwrap();
+        assert!(small.len() < bytes.len());
+        let read = BinaryTrajectory::from_bytes(&small).unwrap();
+        let (Sample::Swerve { x, .. }, Sample::Swerve { x: original, .. }) =
+            (read.trajectory.samples[10], file.trajectory.samples[10])
+        else {
+            panic!("expected swerve samples");
+        };
+        assert!((x - original).abs() < 1e-5);
+        assert_eq!(
+            BinaryTrajectory::precision(&small[..]).unwrap(),
+            Precision::F32
+        );
+        assert_eq!(
+            BinaryTrajectory::precision(&bytes[..]).unwrap(),
+            Precision::F64
+        );
+
+        assert!(BinaryTrajectory::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(BinaryTrajectory::from_bytes(b"JSON").is_err());
+        // A name claiming to be 4 GB long
+        let mut huge = bytes[..8].to_vec();
+        huge.extend(u32::MAX.to_le_bytes());
+        huge.extend(b"name");
+        assert!(BinaryTrajectory::from_bytes(&huge).is_err());
+    }
+
+    #[test]
+    fn corrupt_marker() {
+        let binary = BinaryTrajectory {
+            name: "a".to_string(),
+            trajectory: Trajectory {
+                sample_type: None,
+                waypoints: vec![],
+                samples: vec![],
+                splits: vec![],
+            },
+            events: vec![EventMarker {
+                name: "b".to_string(),
+                from: EventMarkerData {
+                    target: None,
+                    target_timestamp: None,
+                    offset: Expr::new("0 s", 0.0),
+                },
+                event: None,
+            }],
+        };
+        let mut bytes = binary.to_bytes(Precision::F64).unwrap();
+        // The header, the name, three empty lists, the marker count, name and target
+        let flag = 8 + 5 + 3 * 4 + 4 + 5 + 1;
+        assert_eq!(bytes[flag], 0);
+        assert!(BinaryTrajectory::from_bytes(&bytes).is_ok());
+        bytes[flag] = 2;
+        let error = BinaryTrajectory::from_bytes(&bytes).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Unknown marker timestamp flag 2"));
+    }
+}
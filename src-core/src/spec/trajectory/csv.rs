@@ -0,0 +1,207 @@
+use crate::{spec::project::RobotConfig, ChoreoError, ChoreoResult};
+
+use super::{DriveType, Sample, Trajectory};
+
+/// The columns of a swerve sample, in the order they are written.
+const SWERVE_COLUMNS: [&str; 18] = [
+    "t", "x", "y", "heading", "vx", "vy", "omega", "ax", "ay", "alpha", "fx0", "fx1", "fx2", "fx3",
+    "fy0", "fy1", "fy2", "fy3",
+];
+
+/// The columns of a differential sample, in the order they are written.
+const DIFFERENTIAL_COLUMNS: [&str; 11] = [
+    "t", "x", "y", "heading", "vl", "vr", "omega", "al", "ar", "fl", "fr",
+];
+
+/// The columns of each module state, prefixed with the index of the module.
+const MODULE_STATE_COLUMNS: [&str; 4] = ["speed", "azimuth", "azimuth_rate", "torque"];
+
+/// Which derived columns to add after the sample fields when exporting to CSV.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CsvColumns<'a> {
+    /// Add the speed of the robot along the ground, in meters / second.
+    pub speed: bool,
+    /// Add the state of each swerve module for this robot, see
+    /// [`Sample::module_states`]. Ignored for differential trajectories.
+    pub module_states: Option<&'a RobotConfig<f64>>,
+}
+
+impl Trajectory {
+    /// The samples as CSV, with a header row and one row per sample.
+    ///
+    /// The columns are the fields of the sample type, with module forces as `fx0`..`fy3`
+    /// for swerve, followed by any derived `columns`. Values are in SI units.
+    ///
+    /// Fails if the samples are not all of the same drive type.
+    pub fn to_csv(&self, columns: &CsvColumns) -> ChoreoResult<String> {
+        let drive_type = self
+            .sample_type
+            .or(match self.samples.first() {
+                Some(Sample::Swerve { .. }) => Some(DriveType::Swerve),
+                Some(Sample::DifferentialDrive { .. }) => Some(DriveType::Differential),
+                None => None,
+            })
+            .unwrap_or(DriveType::Swerve);
+        let module_states = columns
+            .module_states
+            .filter(|_| drive_type == DriveType::Swerve);
+
+        let mut header = match drive_type {
+            DriveType::Swerve => SWERVE_COLUMNS.map(String::from).to_vec(),
+            DriveType::Differential => DIFFERENTIAL_COLUMNS.map(String::from).to_vec(),
+        };
+        if columns.speed {
+            header.push("speed".to_string());
+        }
+        if let Some(config) = module_states {
+            for i in 0..config.module_translations().len() {
+                header.extend(MODULE_STATE_COLUMNS.map(|column| format!("module{i}_{column}")));
+            }
+        }
+
+        let mut csv = header.join(",");
+        csv.push('\n');
+        for sample in &self.samples {
+            let mut row = match (*sample, drive_type) {
+                (
+                    Sample::Swerve {
+                        t,
+                        x,
+                        y,
+                        heading,
+                        vx,
+                        vy,
+                        omega,
+                        ax,
+                        ay,
+                        alpha,
+                        fx,
+                        fy,
+                    },
+                    DriveType::Swerve,
+                ) => {
+                    let mut row = vec![t, x, y, heading, vx, vy, omega, ax, ay, alpha];
+                    row.extend(fx);
+                    row.extend(fy);
+                    if columns.speed {
+                        row.push(vx.hypot(vy));
+                    }
+                    row
+                }
+                (
+                    Sample::DifferentialDrive {
+                        t,
+                        x,
+                        y,
+                        heading,
+                        vl,
+                        vr,
+                        omega,
+                        al,
+                        ar,
+                        fl,
+                        fr,
+                    },
+                    DriveType::Differential,
+                ) => {
+                    let mut row = vec![t, x, y, heading, vl, vr, omega, al, ar, fl, fr];
+                    if columns.speed {
+                        row.push(((vl + vr) / 2.0).abs());
+                    }
+                    row
+                }
+                _ => {
+                    return Err(ChoreoError::Export(format!(
+                        "The sample at {} s is not a {drive_type:?} sample",
+                        sample.t()
+                    )))
+                }
+            };
+            if let Some(states) = module_states.and_then(|config| sample.module_states(config)) {
+                for state in states {
+                    row.extend([state.speed, state.azimuth, state.azimuth_rate, state.torque]);
+                }
+            }
+            csv.push_str(
+                &row.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+            csv.push('\n');
+        }
+        Ok(csv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::fixtures::{swerve_config, swerve_trajectory};
+
+    #[test]
+    fn csv_export() {
+        let file = swerve_trajectory();
+        let trajectory = &file.trajectory;
+        let csv = trajectory.to_csv(&CsvColumns::default()).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), trajectory.samples.len() + 1);
+        assert_eq!(lines[0], SWERVE_COLUMNS.join(","));
+        let Sample::Swerve { x, fy, .. } = trajectory.samples[3] else {
+            panic!("expected a swerve sample");
+        };
+        let row = lines[4].split(',').collect::<Vec<_>>();
+        assert_eq!(row.len(), 18);
+        assert_eq!(row[1].parse::<f64>().unwrap(), x);
+        assert_eq!(row[17].parse::<f64>().unwrap(), fy[3]);
+
+        let config = swerve_config();
+        let csv = trajectory
+            .to_csv(&CsvColumns {
+                speed: true,
+                module_states: Some(&config),
+            })
+            .unwrap();
+        let header = csv.lines().next().unwrap().split(',').collect::<Vec<_>>();
+        assert_eq!(header.len(), 18 + 1 + 16);
+        assert_eq!(header[18], "speed");
+        assert_eq!(header[34], "module3_torque");
+        assert!(csv.lines().skip(1).all(|row| row.split(',').count() == 35));
+
+        let differential = Trajectory {
+            sample_type: Some(DriveType::Differential),
+            waypoints: vec![0.0],
+            samples: vec![Sample::DifferentialDrive {
+                t: 0.0,
+                x: 1.0,
+                y: 2.0,
+                heading: 0.0,
+                vl: -1.0,
+                vr: -3.0,
+                omega: 0.0,
+                al: 0.0,
+                ar: 0.0,
+                fl: 0.0,
+                fr: 0.0,
+            }],
+            splits: vec![0],
+        };
+        let csv = differential
+            .to_csv(&CsvColumns {
+                speed: true,
+                module_states: Some(&config),
+            })
+            .unwrap();
+        assert_eq!(
+            csv,
+            format!(
+                "{},speed\n0,1,2,0,-1,-3,0,0,0,0,0,2\n",
+                DIFFERENTIAL_COLUMNS.join(",")
+            )
+        );
+
+        let mut mixed = trajectory.clone();
+        mixed.samples.extend(differential.samples);
+        assert!(mixed.to_csv(&CsvColumns::default()).is_err());
+    }
+}
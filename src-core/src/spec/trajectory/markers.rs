@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use super::{EventMarker, TrajectoryFile};
+
+/// When an event marker fires along its trajectory.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerTime {
+    /// The time the marker fires in seconds, clamped to the duration of the trajectory.
+    pub timestamp: f64,
+    /// Whether the marker's offset put it before the start or after the end of the
+    /// trajectory, so it had to be clamped.
+    pub clamped: bool,
+}
+
+impl EventMarker {
+    /// The time the marker fires, from its target timestamp and offset,
+    /// or `None` if it has no target timestamp.
+    ///
+    /// The target timestamp is only as recent as the last generation,
+    /// see [`TrajectoryFile::resolve_event_markers`].
+    #[must_use]
+    pub fn timestamp(&self) -> Option<f64> {
+        self.from
+            .target_timestamp
+            .map(|target| target + self.from.offset.val)
+    }
+}
+
+impl TrajectoryFile {
+    /// The time of the waypoint targeted by `marker` in the generated trajectory,
+    /// or `None` if it has no target or the trajectory was never generated.
+    pub(super) fn marker_target_timestamp(&self, marker: &EventMarker) -> Option<f64> {
+        let index = marker.from.target?.get_idx(self.params.waypoints.len())?;
+        self.trajectory.waypoints.get(index).copied()
+    }
+
+    /// When `marker` fires along the generated trajectory,
+    /// or `None` if it has no target or the trajectory was never generated.
+    ///
+    /// This uses the current waypoint times rather than the stored target timestamp.
+    #[must_use]
+    pub fn event_marker_time(&self, marker: &EventMarker) -> Option<MarkerTime> {
+        let timestamp = self.marker_target_timestamp(marker)? + marker.from.offset.val;
+        let clamped = timestamp.clamp(0.0, self.trajectory.total_time());
+        Some(MarkerTime {
+            timestamp: clamped,
+            clamped: clamped != timestamp,
+        })
+    }
+
+    /// Update the target timestamp of every event marker to the time of its target waypoint
+    /// in the generated trajectory, clearing it for markers without a valid target.
+    ///
+    /// Returns when each marker fires, in the same order as [`TrajectoryFile::events`].
+    pub fn resolve_event_markers(&mut self) -> Vec<Option<MarkerTime>> {
+        let targets = self
+            .events
+            .iter()
+            .map(|marker| self.marker_target_timestamp(marker))
+            .collect::<Vec<_>>();
+        for (marker, target) in self.events.iter_mut().zip(targets) {
+            marker.from.target_timestamp = target;
+        }
+        self.events
+            .iter()
+            .map(|marker| self.event_marker_time(marker))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{
+        fixtures::swerve_trajectory,
+        trajectory::{EventMarkerData, WaypointID},
+        Expr,
+    };
+
+    #[test]
+    fn resolution() {
+        let mut trajectory = swerve_trajectory();
+        let total = trajectory.trajectory.total_time();
+        let marker = |target, offset| EventMarker {
+            name: "marker".to_string(),
+            from: EventMarkerData {
+                target,
+                target_timestamp: Some(100.0),
+                offset: Expr::new("offset", offset),
+            },
+            event: None,
+        };
+        trajectory.events = vec![
+            marker(Some(WaypointID::Idx(1)), -0.5),
+            marker(Some(WaypointID::Last), 1.0),
+            marker(Some(WaypointID::First), -1.0),
+            marker(Some(WaypointID::Idx(9)), 0.0),
+            marker(None, 0.0),
+        ];
+        let times = trajectory.resolve_event_markers();
+        let waypoint_time = trajectory.trajectory.waypoints[1];
+        assert_eq!(
+            times,
+            vec![
+                Some(MarkerTime {
+                    timestamp: waypoint_time - 0.5,
+                    clamped: false
+                }),
+                Some(MarkerTime {
+                    timestamp: total,
+                    clamped: true
+                }),
+                Some(MarkerTime {
+                    timestamp: 0.0,
+                    clamped: true
+                }),
+                None,
+                None,
+            ]
+        );
+        assert_eq!(
+            trajectory.events[0].from.target_timestamp,
+            Some(waypoint_time)
+        );
+        assert_eq!(trajectory.events[0].timestamp(), Some(waypoint_time - 0.5));
+        assert_eq!(trajectory.events[3].from.target_timestamp, None);
+    }
+}
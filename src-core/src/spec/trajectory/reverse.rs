@@ -0,0 +1,292 @@
+use crate::spec::{Expr, SnapshottableType};
+
+use super::{
+    round, Constraint, Parameters, Sample, Trajectory, TrajectoryFile, Waypoint, WaypointID,
+};
+
+impl WaypointID {
+    /// The same waypoint once the order of `count` waypoints is reversed.
+    #[must_use]
+    pub const fn reversed(&self, count: usize) -> WaypointID {
+        match *self {
+            WaypointID::First => WaypointID::Last,
+            WaypointID::Last => WaypointID::First,
+            WaypointID::Idx(idx) if idx < count => WaypointID::Idx(count - 1 - idx),
+            WaypointID::Idx(idx) => WaypointID::Idx(idx),
+        }
+    }
+}
+
+impl Sample {
+    /// The sample at the same point of the trajectory played backwards from `duration`.
+    ///
+    /// Velocities are negated and accelerations and forces are kept, but they are held
+    /// until the next sample, so they are taken from `previous`, the sample before this
+    /// one in the original trajectory, whose accelerations led here.
+    fn reversed(&self, previous: &Sample, duration: f64) -> Sample {
+        match (*self, *previous) {
+            (
+                Sample::Swerve {
+                    t,
+                    x,
+                    y,
+                    heading,
+                    vx,
+                    vy,
+                    omega,
+                    ..
+                },
+                Sample::Swerve {
+                    ax,
+                    ay,
+                    alpha,
+                    fx,
+                    fy,
+                    ..
+                },
+            ) => Sample::Swerve {
+                t: round(duration - t),
+                x,
+                y,
+                heading,
+                vx: -vx,
+                vy: -vy,
+                omega: -omega,
+                ax,
+                ay,
+                alpha,
+                fx,
+                fy,
+            },
+            (
+                Sample::DifferentialDrive {
+                    t,
+                    x,
+                    y,
+                    heading,
+                    vl,
+                    vr,
+                    omega,
+                    ..
+                },
+                Sample::DifferentialDrive { al, ar, fl, fr, .. },
+            ) => Sample::DifferentialDrive {
+                t: round(duration - t),
+                x,
+                y,
+                heading,
+                vl: -vl,
+                vr: -vr,
+                omega: -omega,
+                al,
+                ar,
+                fl,
+                fr,
+            },
+            _ => *self,
+        }
+    }
+}
+
+impl Trajectory {
+    /// The trajectory played backwards, from its end to its start.
+    ///
+    /// The robot keeps facing the same way, so it drives in reverse.
+    #[must_use]
+    pub fn reversed(&self) -> Trajectory {
+        let duration = self.total_time();
+        let last = self.samples.len().saturating_sub(1);
+        let mut splits = self
+            .splits
+            .iter()
+            .filter(|i| **i != 0 && **i <= last)
+            .map(|i| last - i)
+            .collect::<Vec<_>>();
+        splits.push(0);
+        splits.sort_unstable();
+        splits.dedup();
+        Trajectory {
+            sample_type: self.sample_type,
+            waypoints: self
+                .waypoints
+                .iter()
+                .rev()
+                .map(|t| round(duration - t))
+                .collect(),
+            samples: (0..self.samples.len())
+                .rev()
+                .map(|i| self.samples[i].reversed(&self.samples[i.saturating_sub(1)], duration))
+                .collect(),
+            splits,
+        }
+    }
+}
+
+impl<T: SnapshottableType> Parameters<T> {
+    /// The parameters for the same path in the opposite direction.
+    ///
+    /// Waypoints are reversed, keeping the intervals of the segments between them, and
+    /// constraints are remapped to the same waypoints.
+    #[must_use]
+    pub fn reversed(&self) -> Parameters<T> {
+        let count = self.waypoints.len();
+        let waypoints = (0..count)
+            .rev()
+            .map(|i| {
+                // The segment after the waypoint is the one that used to come before it.
+                let segment = &self.waypoints[i.checked_sub(1).unwrap_or(i)];
+                Waypoint {
+                    intervals: segment.intervals,
+                    override_intervals: segment.override_intervals,
+                    ..self.waypoints[i].clone()
+                }
+            })
+            .collect();
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|constraint| {
+                let from = constraint.from.reversed(count);
+                match constraint.to.map(|to| to.reversed(count)) {
+                    Some(to) => Constraint {
+                        from: to,
+                        to: Some(from),
+                        ..constraint.clone()
+                    },
+                    None => Constraint {
+                        from,
+                        ..constraint.clone()
+                    },
+                }
+            })
+            .collect();
+        Parameters {
+            waypoints,
+            constraints,
+            target_dt: self.target_dt.clone(),
+        }
+    }
+}
+
+impl TrajectoryFile {
+    /// The trajectory file for the same path in the opposite direction,
+    /// see [`Trajectory::reversed`] and [`Parameters::reversed`].
+    ///
+    /// Event markers keep their targets and fire at the same points along the path.
+    #[must_use]
+    pub fn reversed(&self) -> TrajectoryFile {
+        let duration = self.trajectory.total_time();
+        let count = self.params.waypoints.len();
+        let events = self
+            .events
+            .iter()
+            .map(|marker| {
+                let mut reversed = marker.clone();
+                reversed.from.target = marker.from.target.map(|id| id.reversed(count));
+                reversed.from.target_timestamp =
+                    marker.from.target_timestamp.map(|t| round(duration - t));
+                let offset = &marker.from.offset;
+                if offset.val != 0.0 {
+                    reversed.from.offset = Expr::new(&format!("-({})", offset.exp), -offset.val);
+                }
+                reversed
+            })
+            .collect();
+        TrajectoryFile {
+            name: self.name.clone(),
+            version: self.version,
+            snapshot: self.snapshot.as_ref().map(Parameters::reversed),
+            params: self.params.reversed(),
+            trajectory: self.trajectory.reversed(),
+            events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{fixtures::swerve_trajectory, trajectory::DriveType};
+
+    #[test]
+    fn reversed_samples() {
+        let sample = |t: f64| Sample::Swerve {
+            t,
+            x: t * t,
+            y: 0.0,
+            heading: 1.0,
+            vx: 2.0 * t,
+            vy: 0.0,
+            omega: 0.0,
+            ax: 2.0,
+            ay: 0.0,
+            alpha: 0.0,
+            fx: [t; 4],
+            fy: [0.0; 4],
+        };
+        let trajectory = Trajectory {
+            sample_type: Some(DriveType::Swerve),
+            waypoints: vec![0.0, 0.5, 2.0],
+            samples: vec![sample(0.0), sample(1.0), sample(2.0)],
+            splits: vec![0, 1],
+        };
+        let reversed = trajectory.reversed();
+        assert_eq!(reversed.waypoints, vec![0.0, 1.5, 2.0]);
+        assert_eq!(reversed.splits, vec![0, 1]);
+        let Sample::Swerve {
+            t, x, vx, ax, fx, ..
+        } = reversed.samples[0]
+        else {
+            panic!("expected a swerve sample");
+        };
+        assert_eq!((t, x, vx, ax, fx[0]), (0.0, 4.0, -4.0, 2.0, 1.0));
+        // Integrating the reversed samples retraces the path
+        let Some(Sample::Swerve { x, vx, .. }) = reversed.sample_at(1.0) else {
+            panic!("expected a swerve sample");
+        };
+        assert_eq!((x, vx), (1.0, -2.0));
+        assert_eq!(reversed.samples[2].pose().x, trajectory.samples[0].pose().x);
+    }
+
+    #[test]
+    fn reversed_parameters() {
+        let trajectory = swerve_trajectory();
+        let reversed = trajectory.reversed();
+        let (params, original) = (&reversed.params, &trajectory.params);
+        assert_eq!(params.waypoints[0].x.val, original.waypoints[2].x.val);
+        assert_eq!(
+            params
+                .waypoints
+                .iter()
+                .map(|w| w.intervals)
+                .collect::<Vec<_>>(),
+            vec![27, 27, 27]
+        );
+        let ids = params
+            .constraints
+            .iter()
+            .map(|c| (c.from, c.to))
+            .collect::<Vec<_>>();
+        assert_eq!(ids[0], (WaypointID::Last, None));
+        assert_eq!(ids[2], (WaypointID::First, Some(WaypointID::Last)));
+        assert_eq!(ids[4], (WaypointID::Idx(0), Some(WaypointID::Idx(0))));
+        assert_eq!(ids[8], (WaypointID::Idx(1), Some(WaypointID::Idx(2))));
+        assert_eq!(reversed.up_to_date(), trajectory.up_to_date());
+        assert_eq!(
+            reversed.reversed().params.snapshot(),
+            Parameters {
+                waypoints: original
+                    .waypoints
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| Waypoint {
+                        intervals: if i == 2 { 27 } else { w.intervals },
+                        ..w.clone()
+                    })
+                    .collect(),
+                ..original.clone()
+            }
+            .snapshot()
+        );
+    }
+}
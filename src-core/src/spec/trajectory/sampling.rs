@@ -0,0 +1,437 @@
+use trajoptlib::Pose2d;
+
+use crate::{generation::angle_modulus, ChoreoError, ChoreoResult};
+
+use super::{round, Sample, Trajectory};
+
+/// The resolution sample timestamps are rounded to, and so the shortest sample period.
+///
+/// Units: seconds
+const MIN_SAMPLE_PERIOD: f64 = 1e-5;
+
+/// `from + (to - from) * scale`
+fn lerp(from: f64, to: f64, scale: f64) -> f64 {
+    from + (to - from) * scale
+}
+
+/// The value after `dt` seconds of constant `acceleration`.
+fn integrate(start: f64, velocity: f64, acceleration: f64, dt: f64) -> f64 {
+    start + velocity * dt + 0.5 * acceleration * dt * dt
+}
+
+/// The displacement and final heading of a differential drive after `dt` seconds,
+/// travelling along the average of its start and end headings.
+fn drive(
+    (speed, acceleration): (f64, f64),
+    (heading, omega, alpha): (f64, f64, f64),
+    dt: f64,
+) -> (f64, f64, f64) {
+    let distance = integrate(0.0, speed, acceleration, dt);
+    let turned = integrate(0.0, omega, alpha, dt);
+    let travel = heading + turned / 2.0;
+    (
+        distance * travel.cos(),
+        distance * travel.sin(),
+        heading + turned,
+    )
+}
+
+impl Sample {
+    /// The timestamp of the sample in seconds.
+    #[must_use]
+    pub const fn t(&self) -> f64 {
+        match self {
+            Sample::Swerve { t, .. } | Sample::DifferentialDrive { t, .. } => *t,
+        }
+    }
+
+    /// The same state of the robot with its timestamp set to `time` seconds.
+    #[must_use]
+    pub fn with_time(&self, time: f64) -> Sample {
+        let mut sample = *self;
+        match &mut sample {
+            Sample::Swerve { t, .. } | Sample::DifferentialDrive { t, .. } => *t = time,
+        }
+        sample
+    }
+
+    /// The pose of the robot at the sample (blue origin).
+    #[must_use]
+    pub const fn pose(&self) -> Pose2d {
+        match *self {
+            Sample::Swerve { x, y, heading, .. }
+            | Sample::DifferentialDrive { x, y, heading, .. } => Pose2d { x, y, heading },
+        }
+    }
+
+    /// The state of the robot at time `t` between this sample and `end`.
+    ///
+    /// Positions, headings and velocities are integrated from this sample's
+    /// accelerations, which the generator holds constant until the next sample.
+    /// Samples are rounded when written to file, so the integrated state is corrected
+    /// linearly to land exactly on `end`, with headings taking the short way around.
+    /// Accelerations are held and forces are interpolated linearly.
+    ///
+    /// `t` is clamped to the time between the samples. If the samples are of different
+    /// drive types, this sample is returned.
+    #[must_use]
+    pub fn interpolate(&self, end: &Sample, t: f64) -> Sample {
+        let duration = end.t() - self.t();
+        if duration <= 0.0 {
+            return *self;
+        }
+        let dt = (t - self.t()).clamp(0.0, duration);
+        let scale = dt / duration;
+        // The integrated value at `dt`, plus the share of the error at `end` so far
+        let correct = |at_dt: f64, at_end: f64, end: f64| at_dt + (end - at_end) * scale;
+        let correct_angle = |at_dt: f64, at_end: f64, end: f64| {
+            angle_modulus(at_dt + angle_modulus(end - at_end) * scale)
+        };
+        match (*self, *end) {
+            (
+                Sample::Swerve {
+                    x,
+                    y,
+                    heading,
+                    vx,
+                    vy,
+                    omega,
+                    ax,
+                    ay,
+                    alpha,
+                    fx,
+                    fy,
+                    ..
+                },
+                Sample::Swerve {
+                    x: end_x,
+                    y: end_y,
+                    heading: end_heading,
+                    vx: end_vx,
+                    vy: end_vy,
+                    omega: end_omega,
+                    fx: end_fx,
+                    fy: end_fy,
+                    ..
+                },
+            ) => {
+                let state = |start, velocity, acceleration, end| {
+                    correct(
+                        integrate(start, velocity, acceleration, dt),
+                        integrate(start, velocity, acceleration, duration),
+                        end,
+                    )
+                };
+                Sample::Swerve {
+                    t: self.t() + dt,
+                    x: state(x, vx, ax, end_x),
+                    y: state(y, vy, ay, end_y),
+                    heading: correct_angle(
+                        integrate(heading, omega, alpha, dt),
+                        integrate(heading, omega, alpha, duration),
+                        end_heading,
+                    ),
+                    vx: state(vx, ax, 0.0, end_vx),
+                    vy: state(vy, ay, 0.0, end_vy),
+                    omega: state(omega, alpha, 0.0, end_omega),
+                    ax,
+                    ay,
+                    alpha,
+                    fx: std::array::from_fn(|i| lerp(fx[i], end_fx[i], scale)),
+                    fy: std::array::from_fn(|i| lerp(fy[i], end_fy[i], scale)),
+                }
+            }
+            (
+                Sample::DifferentialDrive {
+                    x,
+                    y,
+                    heading,
+                    vl,
+                    vr,
+                    omega,
+                    al,
+                    ar,
+                    fl,
+                    fr,
+                    ..
+                },
+                Sample::DifferentialDrive {
+                    x: end_x,
+                    y: end_y,
+                    heading: end_heading,
+                    vl: end_vl,
+                    vr: end_vr,
+                    omega: end_omega,
+                    fl: end_fl,
+                    fr: end_fr,
+                    ..
+                },
+            ) => {
+                // The track width isn't stored, so the angular acceleration
+                // is recovered from the change in angular velocity.
+                let alpha = (end_omega - omega) / duration;
+                let linear = ((vl + vr) / 2.0, (al + ar) / 2.0);
+                let (dx, dy, turned) = drive(linear, (heading, omega, alpha), dt);
+                let (end_dx, end_dy, end_turned) = drive(linear, (heading, omega, alpha), duration);
+                Sample::DifferentialDrive {
+                    t: self.t() + dt,
+                    x: correct(x + dx, x + end_dx, end_x),
+                    y: correct(y + dy, y + end_dy, end_y),
+                    heading: correct_angle(turned, end_turned, end_heading),
+                    vl: correct(
+                        integrate(vl, al, 0.0, dt),
+                        integrate(vl, al, 0.0, duration),
+                        end_vl,
+                    ),
+                    vr: correct(
+                        integrate(vr, ar, 0.0, dt),
+                        integrate(vr, ar, 0.0, duration),
+                        end_vr,
+                    ),
+                    omega: lerp(omega, end_omega, scale),
+                    al,
+                    ar,
+                    fl: lerp(fl, end_fl, scale),
+                    fr: lerp(fr, end_fr, scale),
+                }
+            }
+            _ => *self,
+        }
+    }
+}
+
+impl Trajectory {
+    /// The duration of the trajectory in seconds, or 0 if it has no samples.
+    #[must_use]
+    pub fn total_time(&self) -> f64 {
+        self.samples.last().map_or(0.0, Sample::t)
+    }
+
+    /// The pose at the start of the trajectory, if it has samples.
+    #[must_use]
+    pub fn initial_pose(&self) -> Option<Pose2d> {
+        self.samples.first().map(Sample::pose)
+    }
+
+    /// The pose at the end of the trajectory, if it has samples.
+    #[must_use]
+    pub fn final_pose(&self) -> Option<Pose2d> {
+        self.samples.last().map(Sample::pose)
+    }
+
+    /// The state of the robot at time `t` seconds, see [`Sample::interpolate`].
+    ///
+    /// Times before the start or after the end of the trajectory return the first
+    /// or last sample. Returns `None` if the trajectory has no samples or `t` is NaN.
+    #[must_use]
+    pub fn sample_at(&self, t: f64) -> Option<Sample> {
+        if t.is_nan() {
+            return None;
+        }
+        let next = self.samples.partition_point(|sample| sample.t() <= t);
+        match (next.checked_sub(1), self.samples.get(next)) {
+            (Some(previous), Some(next)) => Some(self.samples[previous].interpolate(next, t)),
+            (Some(previous), None) => Some(self.samples[previous]),
+            (None, next) => next.copied(),
+        }
+    }
+
+    /// The trajectory sampled every `dt` seconds, see [`Trajectory::sample_at`].
+    ///
+    /// Each split section is resampled from its start, so splits still land on samples,
+    /// and ends with its last sample even if that is less than `dt` after the one before.
+    /// The waypoint times are kept. Fails if `dt` is shorter than the 1e-5 s resolution
+    /// that timestamps are rounded to, which would give samples the same timestamp.
+    pub fn resampled(&self, dt: f64) -> ChoreoResult<Trajectory> {
+        if dt.is_nan() || dt < MIN_SAMPLE_PERIOD {
+            return Err(ChoreoError::Calculation(format!(
+                "Sample period {dt} s must be at least {MIN_SAMPLE_PERIOD} s"
+            )));
+        }
+        let mut starts = self
+            .splits
+            .iter()
+            .filter_map(|i| self.samples.get(*i).map(Sample::t))
+            .collect::<Vec<_>>();
+        starts.extend(self.samples.first().map(Sample::t));
+        starts.sort_by(f64::total_cmp);
+        starts.dedup();
+        let mut samples = Vec::new();
+        let mut splits = Vec::new();
+        for (i, start) in starts.iter().enumerate() {
+            let end = starts.get(i + 1).copied().unwrap_or(self.total_time());
+            splits.push(samples.len());
+            let mut t = *start;
+            let mut step = 0.0;
+            // Stop short of the end so rounding can't leave a tiny final step
+            while t < end - dt * 1e-3 {
+                samples.extend(self.sample_at(t).map(|s| s.with_time(t)));
+                step += 1.0;
+                t = round(start + step * dt);
+            }
+        }
+        samples.extend(self.samples.last().copied());
+        // The last sample is never a split
+        splits.retain(|i| *i == 0 || *i + 1 < samples.len());
+        Ok(Trajectory {
+            sample_type: self.sample_type,
+            waypoints: self.waypoints.clone(),
+            samples,
+            splits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::spec::trajectory::DriveType;
+
+    fn trajectory(samples: Vec<Sample>) -> Trajectory {
+        Trajectory {
+            sample_type: Some(DriveType::Swerve),
+            waypoints: vec![],
+            samples,
+            splits: vec![0],
+        }
+    }
+
+    fn swerve(t: f64, x: f64, heading: f64, vx: f64, omega: f64) -> Sample {
+        Sample::Swerve {
+            t,
+            x,
+            y: 1.0,
+            heading,
+            vx,
+            vy: 0.0,
+            omega,
+            ax: 2.0,
+            ay: 0.0,
+            alpha: 0.0,
+            fx: [t; 4],
+            fy: [0.0; 4],
+        }
+    }
+
+    #[test]
+    fn swerve_sampling() {
+        let trajectory = trajectory(vec![
+            swerve(0.0, 0.0, 3.0, 0.0, 1.0),
+            swerve(1.0, 1.0, 4.0 - 2.0 * PI, 2.0, 1.0),
+        ]);
+        assert_eq!(trajectory.total_time(), 1.0);
+        assert_eq!(trajectory.initial_pose().unwrap().heading, 3.0);
+        assert_eq!(trajectory.final_pose().unwrap().x, 1.0);
+
+        let Some(Sample::Swerve {
+            t,
+            x,
+            y,
+            heading,
+            vx,
+            fx,
+            ..
+        }) = trajectory.sample_at(0.5)
+        else {
+            panic!("expected a swerve sample");
+        };
+        assert_eq!((t, x, y, vx, fx[0]), (0.5, 0.25, 1.0, 1.0, 0.5));
+        assert!((heading - angle_modulus(3.5)).abs() < 1e-9);
+
+        let Some(Sample::Swerve { heading, .. }) = trajectory.sample_at(0.25 + PI - 3.0) else {
+            panic!("expected a swerve sample");
+        };
+        assert!(heading < -PI + 0.3);
+
+        assert_eq!(
+            trajectory.sample_at(-1.0),
+            trajectory.samples.first().copied()
+        );
+        assert_eq!(
+            trajectory.sample_at(2.0),
+            trajectory.samples.last().copied()
+        );
+        assert_eq!(trajectory.sample_at(f64::NAN), None);
+        assert_eq!(
+            Trajectory {
+                samples: vec![],
+                ..trajectory
+            }
+            .sample_at(0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn differential_sampling() {
+        let sample = |t: f64, x: f64, v: f64| Sample::DifferentialDrive {
+            t,
+            x,
+            y: 0.0,
+            heading: 0.0,
+            vl: v,
+            vr: v,
+            omega: 0.0,
+            al: 1.0,
+            ar: 1.0,
+            fl: 0.0,
+            fr: 0.0,
+        };
+        let trajectory = trajectory(vec![sample(0.0, 0.0, 0.0), sample(2.0, 2.0, 2.0)]);
+        let Some(Sample::DifferentialDrive { x, vl, heading, .. }) = trajectory.sample_at(1.0)
+        else {
+            panic!("expected a differential sample");
+        };
+        assert_eq!((x, vl, heading), (0.5, 1.0, 0.0));
+    }
+
+    #[test]
+    fn resampling() {
+        let sample = |t: f64| swerve(t, t * t, 0.0, 2.0 * t, 0.0);
+        let trajectory = Trajectory {
+            waypoints: vec![0.0, 0.3, 1.0],
+            splits: vec![0, 1],
+            ..trajectory(vec![sample(0.0), sample(0.3), sample(1.0)])
+        };
+        let resampled = trajectory.resampled(0.2).unwrap();
+        assert_eq!(
+            resampled.samples.iter().map(Sample::t).collect::<Vec<_>>(),
+            vec![0.0, 0.2, 0.3, 0.5, 0.7, 0.9, 1.0]
+        );
+        assert_eq!(resampled.splits, vec![0, 2]);
+        assert_eq!(resampled.waypoints, trajectory.waypoints);
+        let Sample::Swerve { x, vx, .. } = resampled.samples[3] else {
+            panic!("expected a swerve sample");
+        };
+        assert!((x - 0.25).abs() < 1e-9 && (vx - 1.0).abs() < 1e-9);
+        assert!(trajectory.resampled(0.0).is_err());
+        assert!(trajectory.resampled(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn resampling_too_finely() {
+        let long = trajectory(vec![
+            swerve(0.0, 0.0, 0.0, 0.0, 0.0),
+            swerve(10.0, 1.0, 0.0, 0.0, 0.0),
+        ]);
+        // Would be about 1e10 samples, most with the same rounded timestamp
+        assert!(matches!(
+            long.resampled(1e-9),
+            Err(ChoreoError::Calculation(_))
+        ));
+        assert!(long.resampled(-1.0).is_err());
+
+        let short = trajectory(vec![
+            swerve(0.0, 0.0, 0.0, 0.0, 0.0),
+            swerve(0.1, 1.0, 0.0, 0.0, 0.0),
+        ]);
+        let finest = short.resampled(MIN_SAMPLE_PERIOD).unwrap();
+        assert_eq!(finest.samples.len(), 10_001);
+        assert!(finest
+            .samples
+            .windows(2)
+            .all(|pair| pair[0].t() < pair[1].t()));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::spec::SnapshottableType;
+
+use super::{
+    round, Constraint, ConstraintScope, Parameters, Trajectory, TrajectoryFile, WaypointID,
+};
+
+/// The waypoints `start..=end` of `params`, with the constraints that apply to them.
+///
+/// Constraints on segments that cross the ends of the range are cut down to the range.
+fn sub_parameters<T: SnapshottableType>(
+    params: &Parameters<T>,
+    start: usize,
+    end: usize,
+) -> Parameters<T> {
+    let count = params.waypoints.len();
+    let constraints = params
+        .constraints
+        .iter()
+        .filter_map(|constraint| {
+            let from = constraint.from.get_idx(count)?;
+            let (from, to) = match constraint.to.and_then(|id| id.get_idx(count)) {
+                Some(to) if to != from => {
+                    let (from, to) = (from.min(to).max(start), from.max(to).min(end));
+                    if from >= to {
+                        return None;
+                    }
+                    (from, Some(to))
+                }
+                _ if (start..=end).contains(&from)
+                    && constraint.data.scope() != ConstraintScope::Segment =>
+                {
+                    (from, None)
+                }
+                _ => return None,
+            };
+            Some(Constraint {
+                from: WaypointID::Idx(from - start),
+                to: to.map(|to| WaypointID::Idx(to - start)),
+                ..constraint.clone()
+            })
+        })
+        .collect();
+    Parameters {
+        waypoints: params.waypoints[start..=end].to_vec(),
+        constraints,
+        target_dt: params.target_dt.clone(),
+    }
+}
+
+impl TrajectoryFile {
+    /// The sections of the generated trajectory between its split waypoints,
+    /// each as its own trajectory file named `{name}_{index}`.
+    ///
+    /// Each section starts at time zero. It keeps the waypoints it passes through and the
+    /// constraints on them, and the event markers that fire during it, with their targets
+    /// renumbered to match, see [`TrajectoryFile::event_marker_time`].
+    ///
+    /// Returns nothing if the trajectory was never generated.
+    #[must_use]
+    pub fn split(&self) -> Vec<TrajectoryFile> {
+        let samples = &self.trajectory.samples;
+        if samples.is_empty() {
+            return Vec::new();
+        }
+        let mut splits = self
+            .trajectory
+            .splits
+            .iter()
+            .copied()
+            .filter(|i| *i < samples.len())
+            .collect::<Vec<_>>();
+        splits.push(0);
+        splits.sort_unstable();
+        splits.dedup();
+
+        // The sample index of each waypoint, from the intervals used to generate it.
+        let waypoint_samples = self
+            .params
+            .waypoints
+            .iter()
+            .scan(0, |sample, waypoint| {
+                let index = *sample;
+                *sample += waypoint.intervals;
+                Some(index)
+            })
+            .collect::<Vec<_>>();
+        let waypoint_at = |sample: usize| {
+            waypoint_samples
+                .iter()
+                .position(|s| *s == sample)
+                .unwrap_or(0)
+        };
+
+        let sections = splits
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                (
+                    *start,
+                    splits.get(i + 1).copied().unwrap_or(samples.len() - 1),
+                )
+            })
+            .collect::<Vec<_>>();
+        let section_of = |time: f64| {
+            sections
+                .iter()
+                .rposition(|(start, _)| samples[*start].t() <= time)
+                .unwrap_or(0)
+        };
+
+        sections
+            .iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                let t0 = samples[*start].t();
+                let first_waypoint = waypoint_at(*start);
+                let last_waypoint = if i + 1 == sections.len() {
+                    self.params.waypoints.len().saturating_sub(1)
+                } else {
+                    waypoint_at(*end)
+                }
+                .max(first_waypoint);
+                let waypoints = first_waypoint..=last_waypoint;
+                let events = self
+                    .events
+                    .iter()
+                    .filter(|marker| {
+                        self.event_marker_time(marker)
+                            .is_some_and(|time| section_of(time.timestamp) == i)
+                    })
+                    .map(|marker| {
+                        let mut moved = marker.clone();
+                        moved.from.target = marker
+                            .from
+                            .target
+                            .and_then(|id| id.get_idx(self.params.waypoints.len()))
+                            .filter(|target| waypoints.contains(target))
+                            .map(|target| WaypointID::Idx(target - first_waypoint));
+                        moved.from.target_timestamp =
+                            self.marker_target_timestamp(marker).map(|t| round(t - t0));
+                        moved
+                    })
+                    .collect();
+                TrajectoryFile {
+                    name: format!("{}_{i}", self.name),
+                    version: self.version,
+                    snapshot: self
+                        .snapshot
+                        .as_ref()
+                        .filter(|s| s.waypoints.len() > last_waypoint)
+                        .map(|s| sub_parameters(s, first_waypoint, last_waypoint)),
+                    params: if self.params.waypoints.is_empty() {
+                        self.params.clone()
+                    } else {
+                        sub_parameters(&self.params, first_waypoint, last_waypoint)
+                    },
+                    trajectory: Trajectory {
+                        sample_type: self.trajectory.sample_type,
+                        waypoints: self
+                            .trajectory
+                            .waypoints
+                            .get(waypoints)
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|t| round(t - t0))
+                            .collect(),
+                        samples: samples[*start..=*end]
+                            .iter()
+                            .map(|s| s.with_time(round(s.t() - t0)))
+                            .collect(),
+                        splits: vec![0],
+                    },
+                    events,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{
+        trajectory::{ConstraintData, DriveType, EventMarker, EventMarkerData, Sample, Waypoint},
+        Expr, TRAJ_SCHEMA_VERSION,
+    };
+
+    fn waypoint(x: f64, split: bool) -> Waypoint<Expr> {
+        Waypoint {
+            x: Expr::new("x", x),
+            y: Expr::new("0 m", 0.0),
+            heading: Expr::new("0 rad", 0.0),
+            pose: None,
+            intervals: 2,
+            split,
+            fix_translation: true,
+            fix_heading: true,
+            override_intervals: false,
+            is_initial_guess: false,
+        }
+    }
+
+    fn marker(name: &str, target: usize, target_timestamp: f64, offset: f64) -> EventMarker {
+        EventMarker {
+            name: name.to_string(),
+            from: EventMarkerData {
+                target: Some(WaypointID::Idx(target)),
+                target_timestamp: Some(target_timestamp),
+                offset: Expr::new("offset", offset),
+            },
+            event: None,
+        }
+    }
+
+    #[test]
+    fn sections() {
+        let constraint = |from, to, data| Constraint {
+            from,
+            to,
+            data,
+            enabled: true,
+        };
+        let params = Parameters {
+            waypoints: vec![
+                waypoint(0.0, false),
+                waypoint(1.0, true),
+                waypoint(2.0, false),
+            ],
+            constraints: vec![
+                constraint(
+                    WaypointID::First,
+                    Some(WaypointID::Last),
+                    ConstraintData::MaxVelocity {
+                        max: Expr::new("1 m / s", 1.0),
+                    },
+                ),
+                constraint(WaypointID::Idx(2), None, ConstraintData::StopPoint {}),
+            ],
+            target_dt: Expr::new("0.05 s", 0.05),
+        };
+        let samples = (0..5)
+            .map(|i| Sample::DifferentialDrive {
+                t: f64::from(i) * 0.3,
+                x: f64::from(i) * 0.5,
+                y: 0.0,
+                heading: 0.0,
+                vl: 0.0,
+                vr: 0.0,
+                omega: 0.0,
+                al: 0.0,
+                ar: 0.0,
+                fl: 0.0,
+                fr: 0.0,
+            })
+            .collect();
+        let trajectory = TrajectoryFile {
+            name: "Auto".to_string(),
+            version: TRAJ_SCHEMA_VERSION,
+            snapshot: Some(params.snapshot()),
+            params,
+            trajectory: Trajectory {
+                sample_type: Some(DriveType::Differential),
+                waypoints: vec![0.0, 0.6, 1.2],
+                samples,
+                splits: vec![0, 2],
+            },
+            events: vec![
+                marker("start", 0, 0.0, 0.1),
+                marker("late", 0, 0.0, 0.7),
+                marker("end", 2, 1.2, 0.0),
+            ],
+        };
+
+        let sections = trajectory.split();
+        assert_eq!(sections.len(), 2);
+        let [first, second] = sections.as_slice() else {
+            unreachable!();
+        };
+        assert_eq!(first.name, "Auto_0");
+        assert_eq!(first.trajectory.samples.len(), 3);
+        assert_eq!(second.trajectory.samples[0].t(), 0.0);
+        assert_eq!(second.trajectory.samples[2].t(), 0.6);
+        assert_eq!(second.trajectory.waypoints, vec![0.0, 0.6]);
+        assert!(first.up_to_date() && second.up_to_date());
+
+        assert_eq!(second.params.waypoints.len(), 2);
+        assert_eq!(second.params.constraints.len(), 2);
+        assert_eq!(second.params.constraints[0].from, WaypointID::Idx(0));
+        assert_eq!(second.params.constraints[0].to, Some(WaypointID::Idx(1)));
+        assert_eq!(second.params.constraints[1].from, WaypointID::Idx(1));
+        assert_eq!(first.params.constraints.len(), 1);
+
+        assert_eq!(first.events.len(), 1);
+        let late = &second.events[0];
+        assert_eq!(late.name, "late");
+        assert_eq!(late.from.target, None);
+        assert_eq!(late.from.target_timestamp, Some(-0.6));
+        let end = &second.events[1];
+        assert_eq!(end.from.target, Some(WaypointID::Idx(1)));
+        assert_eq!(end.from.target_timestamp, Some(0.6));
+    }
+}
@@ -0,0 +1,403 @@
+use crate::{
+    generation::angle_modulus,
+    spec::{SnapshottableType, TRAJ_SCHEMA_VERSION},
+    ChoreoError, ChoreoResult,
+};
+
+use super::{
+    round, Constraint, DriveType, Parameters, Sample, Trajectory, TrajectoryFile, Waypoint,
+    WaypointID,
+};
+
+/// How far apart the end of one trajectory and the start of the next can be
+/// when stitching them together.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StitchTolerance {
+    /// The distance between the positions.
+    ///
+    /// Units: meters
+    pub position: f64,
+    /// The difference between the headings.
+    ///
+    /// Units: radians
+    pub heading: f64,
+    /// The difference between each component of the velocities.
+    ///
+    /// Units: meters / second or radians / second
+    pub velocity: f64,
+}
+
+impl Default for StitchTolerance {
+    fn default() -> Self {
+        Self {
+            position: 0.01,
+            heading: 0.01,
+            velocity: 0.01,
+        }
+    }
+}
+
+const fn drive_type(sample: &Sample) -> DriveType {
+    match sample {
+        Sample::Swerve { .. } => DriveType::Swerve,
+        Sample::DifferentialDrive { .. } => DriveType::Differential,
+    }
+}
+
+/// The velocities of the sample, in the same order for both drive types.
+const fn velocities(sample: &Sample) -> [f64; 3] {
+    match *sample {
+        Sample::Swerve { vx, vy, omega, .. } => [vx, vy, omega],
+        Sample::DifferentialDrive { vl, vr, omega, .. } => [vl, vr, omega],
+    }
+}
+
+/// Check that `start` carries on from `end` within `tolerance`.
+fn check_continuity(
+    end: &Sample,
+    start: &Sample,
+    tolerance: &StitchTolerance,
+) -> Result<(), String> {
+    let (end_pose, start_pose) = (end.pose(), start.pose());
+    let distance = (start_pose.x - end_pose.x).hypot(start_pose.y - end_pose.y);
+    if distance > tolerance.position {
+        return Err(format!("the positions are {distance} m apart"));
+    }
+    let turn = angle_modulus(start_pose.heading - end_pose.heading).abs();
+    if turn > tolerance.heading {
+        return Err(format!("the headings are {turn} rad apart"));
+    }
+    let jump = velocities(end)
+        .iter()
+        .zip(velocities(start))
+        .map(|(end, start)| (start - end).abs())
+        .fold(0.0, f64::max);
+    if jump > tolerance.velocity {
+        return Err(format!("the velocities differ by up to {jump}"));
+    }
+    Ok(())
+}
+
+/// The parameters of each part, joined end to end.
+///
+/// The first waypoint of each part after the first is the last waypoint of the one
+/// before, so it is dropped, and the waypoint they share is marked as a split. It takes
+/// the intervals of the dropped waypoint, since they are those of the segment after it.
+fn join_parameters<T: SnapshottableType>(parts: &[&Parameters<T>]) -> Parameters<T> {
+    let mut waypoints: Vec<Waypoint<T>> = Vec::new();
+    let mut constraints = Vec::new();
+    for part in parts {
+        let offset = waypoints.len().saturating_sub(1);
+        let count = part.waypoints.len();
+        let reindex = |id: WaypointID| id.get_idx(count).map(|i| WaypointID::Idx(i + offset));
+        constraints.extend(part.constraints.iter().filter_map(|constraint| {
+            Some(Constraint {
+                from: reindex(constraint.from)?,
+                to: match constraint.to {
+                    Some(to) => Some(reindex(to)?),
+                    None => None,
+                },
+                ..constraint.clone()
+            })
+        }));
+        if let Some(joint) = waypoints.last_mut() {
+            joint.split = true;
+            if let Some(first) = part.waypoints.first() {
+                joint.intervals = first.intervals;
+                joint.override_intervals = first.override_intervals;
+            }
+            waypoints.extend(part.waypoints.iter().skip(1).cloned());
+        } else {
+            waypoints.extend(part.waypoints.iter().cloned());
+        }
+    }
+    Parameters {
+        waypoints,
+        constraints,
+        target_dt: parts
+            .first()
+            .map_or_else(|| T::fill_in_value(0.05, "s"), |p| p.target_dt.clone()),
+    }
+}
+
+impl TrajectoryFile {
+    /// Join generated trajectories end to end into one trajectory named `name`.
+    ///
+    /// Each trajectory after the first is shifted in time to start where the one before
+    /// it ends, and must start where it ends, within `tolerance`. The sample and
+    /// waypoint they share appears once, and becomes a split. Parameters, constraints
+    /// and event markers are renumbered to match, and the stitched trajectory has a
+    /// snapshot only if all of the trajectories do.
+    ///
+    /// Fails if there are no trajectories, any of them were never generated, they have
+    /// different drive types, or they aren't continuous.
+    pub fn stitch(
+        name: &str,
+        files: &[TrajectoryFile],
+        tolerance: &StitchTolerance,
+    ) -> ChoreoResult<TrajectoryFile> {
+        let Some(first) = files.first() else {
+            return Err(ChoreoError::Stitch("No trajectories to stitch".to_string()));
+        };
+        let mut samples: Vec<Sample> = Vec::new();
+        let mut splits = Vec::new();
+        let mut waypoint_times: Vec<f64> = Vec::new();
+        let mut events = Vec::new();
+        let mut waypoint_offset = 0;
+        for file in files {
+            let Some(start) = file.trajectory.samples.first() else {
+                return Err(ChoreoError::Stitch(format!(
+                    "{} has not been generated",
+                    file.name
+                )));
+            };
+            let time_offset = match samples.last() {
+                Some(previous) => {
+                    if drive_type(previous) != drive_type(start) {
+                        return Err(ChoreoError::Stitch(format!(
+                            "{} has a different drive type to {}",
+                            file.name, first.name
+                        )));
+                    }
+                    check_continuity(previous, start, tolerance).map_err(|reason| {
+                        ChoreoError::Stitch(format!(
+                            "{} doesn't start where the trajectory before it ends: {reason}",
+                            file.name
+                        ))
+                    })?;
+                    previous.t() - start.t()
+                }
+                None => 0.0,
+            };
+            let skip = usize::from(!samples.is_empty());
+            let sample_offset = samples.len().saturating_sub(1);
+            splits.push(sample_offset);
+            splits.extend(
+                file.trajectory
+                    .splits
+                    .iter()
+                    .filter(|i| **i + 1 < file.trajectory.samples.len())
+                    .map(|i| i + sample_offset),
+            );
+            samples.extend(
+                file.trajectory.samples[skip..]
+                    .iter()
+                    .map(|s| s.with_time(round(s.t() + time_offset))),
+            );
+            waypoint_times.extend(
+                file.trajectory
+                    .waypoints
+                    .iter()
+                    .skip(skip)
+                    .map(|t| round(t + time_offset)),
+            );
+
+            let count = file.params.waypoints.len();
+            events.extend(file.events.iter().map(|marker| {
+                let mut moved = marker.clone();
+                moved.from.target = marker
+                    .from
+                    .target
+                    .and_then(|id| id.get_idx(count))
+                    .map(|i| WaypointID::Idx(i + waypoint_offset));
+                moved.from.target_timestamp =
+                    marker.from.target_timestamp.map(|t| round(t + time_offset));
+                moved
+            }));
+            waypoint_offset += count.saturating_sub(1);
+        }
+        splits.sort_unstable();
+        splits.dedup();
+        splits.retain(|i| *i == 0 || *i + 1 < samples.len());
+
+        let snapshots = files
+            .iter()
+            .map(|file| file.snapshot.as_ref())
+            .collect::<Option<Vec<_>>>();
+        Ok(TrajectoryFile {
+            name: name.to_string(),
+            version: TRAJ_SCHEMA_VERSION,
+            snapshot: snapshots.map(|snapshots| join_parameters(&snapshots)),
+            params: join_parameters(&files.iter().map(|file| &file.params).collect::<Vec<_>>()),
+            trajectory: Trajectory {
+                sample_type: Some(drive_type(&samples[0])),
+                waypoints: waypoint_times,
+                samples,
+                splits,
+            },
+            events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::{
+        trajectory::{ConstraintData, EventMarker, EventMarkerData},
+        Expr,
+    };
+
+    fn waypoint(x: f64) -> Waypoint<Expr> {
+        Waypoint {
+            x: Expr::new("x", x),
+            y: Expr::new("0 m", 0.0),
+            heading: Expr::new("0 rad", 0.0),
+            pose: None,
+            intervals: 2,
+            split: false,
+            fix_translation: true,
+            fix_heading: true,
+            override_intervals: false,
+            is_initial_guess: false,
+        }
+    }
+
+    fn swerve(t: f64, x: f64) -> Sample {
+        Sample::Swerve {
+            t,
+            x,
+            y: 0.0,
+            heading: 0.0,
+            vx: 0.0,
+            vy: 0.0,
+            omega: 0.0,
+            ax: 0.0,
+            ay: 0.0,
+            alpha: 0.0,
+            fx: [0.0; 4],
+            fy: [0.0; 4],
+        }
+    }
+
+    fn file(name: &str, start: f64) -> TrajectoryFile {
+        let params = Parameters {
+            waypoints: vec![waypoint(start), waypoint(start + 1.0)],
+            constraints: vec![
+                Constraint {
+                    from: WaypointID::First,
+                    to: Some(WaypointID::Last),
+                    data: ConstraintData::MaxVelocity {
+                        max: Expr::new("1 m / s", 1.0),
+                    },
+                    enabled: true,
+                },
+                Constraint {
+                    from: WaypointID::Last,
+                    to: None,
+                    data: ConstraintData::StopPoint {},
+                    enabled: true,
+                },
+            ],
+            target_dt: Expr::new("0.05 s", 0.05),
+        };
+        TrajectoryFile {
+            name: name.to_string(),
+            version: TRAJ_SCHEMA_VERSION,
+            snapshot: Some(params.snapshot()),
+            params,
+            trajectory: Trajectory {
+                sample_type: Some(DriveType::Swerve),
+                waypoints: vec![0.0, 1.0],
+                samples: vec![
+                    swerve(0.0, start),
+                    swerve(0.5, start + 0.5),
+                    swerve(1.0, start + 1.0),
+                ],
+                splits: vec![0],
+            },
+            events: vec![EventMarker {
+                name: format!("{name} end"),
+                from: EventMarkerData {
+                    target: Some(WaypointID::Last),
+                    target_timestamp: Some(1.0),
+                    offset: Expr::new("0 s", 0.0),
+                },
+                event: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn stitching() {
+        let tolerance = StitchTolerance::default();
+        let stitched = TrajectoryFile::stitch(
+            "Auto",
+            &[file("A", 0.0), file("B", 1.0), file("C", 2.0)],
+            &tolerance,
+        )
+        .unwrap();
+        let trajectory = &stitched.trajectory;
+        assert_eq!(trajectory.samples.len(), 7);
+        assert_eq!(trajectory.total_time(), 3.0);
+        assert_eq!(trajectory.splits, vec![0, 2, 4]);
+        assert_eq!(trajectory.waypoints, vec![0.0, 1.0, 2.0, 3.0]);
+        assert!(stitched.up_to_date());
+
+        let params = &stitched.params;
+        assert_eq!(params.waypoints.len(), 4);
+        assert!(params.waypoints[1].split && params.waypoints[2].split);
+        assert_eq!(params.constraints.len(), 6);
+        assert_eq!(params.constraints[2].from, WaypointID::Idx(1));
+        assert_eq!(params.constraints[2].to, Some(WaypointID::Idx(2)));
+        assert_eq!(params.constraints[5].from, WaypointID::Idx(3));
+
+        let marker = &stitched.events[1];
+        assert_eq!(marker.from.target, Some(WaypointID::Idx(2)));
+        assert_eq!(marker.from.target_timestamp, Some(2.0));
+        assert_eq!(stitched.event_marker_time(marker).unwrap().timestamp, 2.0);
+    }
+
+    #[test]
+    fn stitching_intervals() {
+        let mut a = file("A", 0.0);
+        a.params.waypoints[0].intervals = 3;
+        a.params.waypoints[1].intervals = 7;
+        let mut b = file("B", 1.0);
+        b.params.waypoints[0].intervals = 5;
+        b.params.waypoints[0].override_intervals = true;
+        b.params.waypoints[1].intervals = 9;
+        let stitched =
+            TrajectoryFile::stitch("Auto", &[a, b], &StitchTolerance::default()).unwrap();
+        let waypoints = &stitched.params.waypoints;
+        assert_eq!(
+            waypoints.iter().map(|w| w.intervals).collect::<Vec<_>>(),
+            vec![3, 5, 9]
+        );
+        assert!(waypoints[1].override_intervals);
+        assert!(!waypoints[0].override_intervals && !waypoints[2].override_intervals);
+    }
+
+    #[test]
+    fn stitching_errors() {
+        let tolerance = StitchTolerance::default();
+        assert!(TrajectoryFile::stitch("Auto", &[], &tolerance).is_err());
+        assert!(
+            TrajectoryFile::stitch("Auto", &[file("A", 0.0), file("B", 1.5)], &tolerance).is_err()
+        );
+
+        let mut differential = file("B", 1.0);
+        differential.trajectory.samples = vec![Sample::DifferentialDrive {
+            t: 0.0,
+            x: 1.0,
+            y: 0.0,
+            heading: 0.0,
+            vl: 0.0,
+            vr: 0.0,
+            omega: 0.0,
+            al: 0.0,
+            ar: 0.0,
+            fl: 0.0,
+            fr: 0.0,
+        }];
+        assert!(
+            TrajectoryFile::stitch("Auto", &[file("A", 0.0), differential], &tolerance).is_err()
+        );
+
+        let mut ungenerated = file("B", 1.0);
+        ungenerated.trajectory.samples.clear();
+        assert!(
+            TrajectoryFile::stitch("Auto", &[file("A", 0.0), ungenerated], &tolerance).is_err()
+        );
+    }
+}
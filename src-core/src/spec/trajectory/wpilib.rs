@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Sample, Trajectory};
+
+/// Below this speed the direction of travel is taken from the acceleration instead,
+/// since it is undefined.
+///
+/// Units: meters / second
+const STOPPED_SPEED: f64 = 1e-6;
+
+/// A translation in WPILib's JSON format.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WpilibTranslation {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A rotation in WPILib's JSON format.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WpilibRotation {
+    pub radians: f64,
+}
+
+/// A pose in WPILib's JSON format.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WpilibPose {
+    pub translation: WpilibTranslation,
+    pub rotation: WpilibRotation,
+}
+
+/// A state of a WPILib `Trajectory`, as read and written by `TrajectoryUtil`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WpilibState {
+    /// Units: seconds
+    pub time: f64,
+    /// The speed along the path, negative when driving backwards.
+    ///
+    /// Units: meters / second
+    pub velocity: f64,
+    /// The acceleration along the path.
+    ///
+    /// Units: meters / second²
+    pub acceleration: f64,
+    /// The position of the robot, and the direction it is travelling in.
+    pub pose: WpilibPose,
+    /// How fast the direction of travel turns with distance along the path.
+    ///
+    /// Units: radians / meter
+    pub curvature: f64,
+}
+
+impl Sample {
+    /// The sample as a state of a WPILib `Trajectory`.
+    ///
+    /// For swerve the rotation of the pose is the direction of travel, as WPILib's
+    /// holonomic controllers expect, and the heading has to be followed separately.
+    /// For differential drive it is the heading, and driving backwards has a negative
+    /// velocity.
+    #[must_use]
+    pub fn to_wpilib(&self) -> WpilibState {
+        let (time, x, y, rotation, velocity, acceleration, curvature) = match *self {
+            Sample::Swerve {
+                t,
+                x,
+                y,
+                vx,
+                vy,
+                ax,
+                ay,
+                ..
+            } => {
+                let speed = vx.hypot(vy);
+                if speed > STOPPED_SPEED {
+                    (
+                        t,
+                        x,
+                        y,
+                        vy.atan2(vx),
+                        speed,
+                        (vx * ax + vy * ay) / speed,
+                        (vx * ay - vy * ax) / speed.powi(3),
+                    )
+                } else {
+                    // About to start moving in the direction of the acceleration
+                    (t, x, y, ay.atan2(ax), speed, ax.hypot(ay), 0.0)
+                }
+            }
+            Sample::DifferentialDrive {
+                t,
+                x,
+                y,
+                heading,
+                vl,
+                vr,
+                omega,
+                al,
+                ar,
+                ..
+            } => {
+                let velocity = (vl + vr) / 2.0;
+                let curvature = if velocity.abs() > STOPPED_SPEED {
+                    omega / velocity
+                } else {
+                    0.0
+                };
+                (t, x, y, heading, velocity, (al + ar) / 2.0, curvature)
+            }
+        };
+        WpilibState {
+            time,
+            velocity,
+            acceleration,
+            pose: WpilibPose {
+                translation: WpilibTranslation { x, y },
+                rotation: WpilibRotation { radians: rotation },
+            },
+            curvature,
+        }
+    }
+}
+
+impl Trajectory {
+    /// The samples as the states of a WPILib `Trajectory`, see [`Sample::to_wpilib`].
+    ///
+    /// Serialized, this is the JSON `TrajectoryUtil` reads.
+    #[must_use]
+    pub fn to_wpilib(&self) -> Vec<WpilibState> {
+        self.samples.iter().map(Sample::to_wpilib).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn wpilib_states() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        // Driving along y while turning right, facing x
+        let swerve = Sample::Swerve {
+            t: 1.0,
+            x: 2.0,
+            y: 3.0,
+            heading: 0.0,
+            vx: 0.0,
+            vy: 2.0,
+            omega: 0.0,
+            ax: 4.0,
+            ay: 1.0,
+            alpha: 0.0,
+            fx: [0.0; 4],
+            fy: [0.0; 4],
+        };
+        let state = swerve.to_wpilib();
+        assert_eq!(state.time, 1.0);
+        assert_eq!(state.pose.translation, WpilibTranslation { x: 2.0, y: 3.0 });
+        assert!(close(state.pose.rotation.radians, FRAC_PI_2));
+        assert!(close(state.velocity, 2.0));
+        assert!(close(state.acceleration, 1.0));
+        // 4 m/s² towards the centre at 2 m/s is a 1 m radius
+        assert!(close(state.curvature, -1.0));
+
+        let reversing = Sample::DifferentialDrive {
+            t: 0.0,
+            x: 0.0,
+            y: 0.0,
+            heading: 1.0,
+            vl: -1.0,
+            vr: -3.0,
+            omega: -1.0,
+            al: 1.0,
+            ar: 0.0,
+            fl: 0.0,
+            fr: 0.0,
+        };
+        let state = reversing.to_wpilib();
+        assert_eq!(state.pose.rotation.radians, 1.0);
+        assert_eq!(state.velocity, -2.0);
+        assert_eq!(state.acceleration, 0.5);
+        assert_eq!(state.curvature, 0.5);
+
+        let json = serde_json::to_value(state).unwrap();
+        assert_eq!(json["pose"]["rotation"]["radians"], 1.0);
+        assert_eq!(json["pose"]["translation"]["x"], 0.0);
+    }
+}
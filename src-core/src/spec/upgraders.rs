@@ -10,6 +10,7 @@ mod traj_file {
         spec::TRAJ_SCHEMA_VERSION,
         ChoreoResult,
     };
+    use serde_json::Value as JsonValue;
     use std::sync::LazyLock;
 
     pub(super) static TRAJ_UPGRADER: LazyLock<Upgrader> = LazyLock::new(make_upgrader);
@@ -17,6 +18,7 @@ mod traj_file {
     fn make_upgrader() -> Upgrader {
         let mut upgrader = Upgrader::new(TRAJ_SCHEMA_VERSION);
         upgrader.add_version_action(up_0_1);
+        upgrader.add_version_action(up_1_2);
         // Ensure the new upgrader is added here
         upgrader
     }
@@ -34,11 +36,25 @@ mod traj_file {
         )
     }
 
+    fn up_1_2(editor: &mut Editor) -> ChoreoResult<()> {
+        // Existing waypoints are not bound to a pose variable
+        for path in ["params.waypoints", "snapshot.waypoints"] {
+            if let Ok(mut waypoints) = editor.get_path::<Vec<JsonValue>>(path) {
+                for waypoint in waypoints.iter_mut().filter_map(JsonValue::as_object_mut) {
+                    waypoint.insert("pose".to_string(), JsonValue::Null);
+                }
+                editor.set_path(path, waypoints)?;
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     mod tests {
         use crate::spec::upgraders::testing_shared::{get_contents, FileType};
         use crate::spec::TRAJ_SCHEMA_VERSION;
         use crate::ChoreoResult;
+        use serde_json::Value as JsonValue;
 
         use crate::spec::trajectory::TrajectoryFile;
         // beta6 is technically the same spec as 0, but with a string version number
@@ -68,6 +84,17 @@ mod traj_file {
             test_trajectory("1", "swerve")
         }
 
+        #[test]
+        pub fn test_2_differential() -> ChoreoResult<()> {
+            test_trajectory("2", "differential")?;
+            test_upgrade("1", "2", "differential")
+        }
+        #[test]
+        pub fn test_2_swerve() -> ChoreoResult<()> {
+            test_trajectory("2", "swerve")?;
+            test_upgrade("1", "2", "swerve")
+        }
+
         /// Tests that the file upgrades to the current version and deserializes properly.
         fn test_trajectory(version: &str, file_name: &str) -> ChoreoResult<()> {
             let contents = get_contents(FileType::Trajectory, version, file_name);
@@ -80,6 +107,20 @@ mod traj_file {
             );
             Ok(())
         }
+
+        /// Tests that upgrading the file from the old version gives exactly the file at the
+        /// new version.
+        fn test_upgrade(old: &str, new: &str, file_name: &str) -> ChoreoResult<()> {
+            let parse = |version| {
+                serde_json::from_str::<JsonValue>(&get_contents(
+                    FileType::Trajectory,
+                    version,
+                    file_name,
+                ))
+            };
+            assert_eq!(super::TRAJ_UPGRADER.upgrade(parse(old)?)?, parse(new)?);
+            Ok(())
+        }
     }
 }
 
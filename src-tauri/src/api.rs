@@ -4,10 +4,14 @@ use std::path::PathBuf;
 
 use crate::tauri::TauriResult;
 use choreo_core::{
-    file_management::{self, create_diagnostic_file, get_log_lines, WritingResources},
+    file_management::{
+        self, create_diagnostic_file, get_log_lines, VariableRename, WritingResources,
+    },
     generation::remote::RemoteGenerationResources,
     spec::{
-        project::{ProjectFile, RobotConfig},
+        expression::{self, DimensionError},
+        lint::{self, Lint},
+        project::{ProjectFile, RobotConfig, Variables},
         trajectory::TrajectoryFile,
         Expr, OpenFilePayload,
     },
@@ -155,6 +159,42 @@ pub async fn trajectory_up_to_date(trajectory: TrajectoryFile) -> bool {
     trajectory.up_to_date()
 }
 
+#[tauri::command]
+pub async fn check_dimensions(
+    project: ProjectFile,
+    trajectory: TrajectoryFile,
+) -> Vec<DimensionError> {
+    expression::check_dimensions(&project, &trajectory)
+}
+
+#[tauri::command]
+pub async fn lint_trajectory(project: ProjectFile, trajectory: TrajectoryFile) -> Vec<Lint> {
+    lint::lint(&trajectory.params, &project.config)
+}
+
+#[tauri::command]
+pub async fn refresh_pose_links(
+    app_handle: tauri::AppHandle,
+    variables: Variables,
+) -> TauriResult<Vec<String>> {
+    let resources = app_handle.state::<WritingResources>();
+    debug_result!(file_management::refresh_pose_links(&resources, &variables).await);
+}
+
+#[tauri::command]
+pub async fn rename_variable(
+    app_handle: tauri::AppHandle,
+    project: ProjectFile,
+    old_name: String,
+    new_name: String,
+    dry_run: bool,
+) -> TauriResult<VariableRename> {
+    let resources = app_handle.state::<WritingResources>();
+    debug_result!(
+        file_management::rename_variable(&resources, project, &old_name, &new_name, dry_run).await
+    );
+}
+
 #[tauri::command]
 pub async fn set_deploy_root(app_handle: tauri::AppHandle, dir: String) {
     let resources = app_handle.state::<WritingResources>();
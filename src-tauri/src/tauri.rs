@@ -208,6 +208,10 @@ pub fn run_tauri(project: Option<PathBuf>) {
             read_trajectory,
             rename_trajectory,
             trajectory_up_to_date,
+            check_dimensions,
+            lint_trajectory,
+            refresh_pose_links,
+            rename_variable,
             set_deploy_root,
             get_deploy_root,
             requested_file,
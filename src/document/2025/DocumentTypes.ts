@@ -31,6 +31,41 @@ export interface Variables {
   poses: Record<string, PoseVariable>;
 }
 
+export interface VariableRename {
+  project: Project;
+  trajectories: string[];
+}
+
+export interface BaseDimensions {
+  length: number;
+  mass: number;
+  time: number;
+  angle: number;
+}
+export interface DimensionError {
+  /** The JSON path of the expression within its file, e.g. `params.waypoints.0.x` */
+  path: string;
+  exp: string;
+  expected: keyof typeof Dimensions;
+  /** null if the expression could not be evaluated */
+  found: BaseDimensions | null;
+  message: string;
+}
+
+/** A problem in a trajectory's parameters. Indices refer to `params.waypoints` and `params.constraints`. */
+export type Lint =
+  | { type: "tooFewWaypoints"; count: number }
+  | { type: "invalidTargetDt" }
+  | { type: "invalidConfig"; field: string }
+  | { type: "zeroIntervals"; waypoint: number }
+  | { type: "waypointOutOfRange"; constraint: number; index: number }
+  | { type: "missingSegment"; constraint: number }
+  | { type: "unexpectedSegment"; constraint: number }
+  | { type: "invalidConstraintValue"; constraint: number; field: string }
+  | { type: "regionTooSmall"; constraint: number }
+  | { type: "outsideKeepIn"; constraint: number; waypoint: number }
+  | { type: "insideKeepOut"; constraint: number; waypoint: number };
+
 export interface Bumper<T extends ExprOrNumber> {
   front: T;
   back: T;
@@ -70,6 +105,7 @@ export interface Waypoint<T extends ExprOrNumber> {
   x: T;
   y: T;
   heading: T;
+  pose: string | null;
   intervals: number;
   split: boolean;
   fixTranslation: boolean;
@@ -1,2 +1,2 @@
 // Auto-generated by update_traj_schema.py
-export const TRAJ_SCHEMA_VERSION = 1;
+export const TRAJ_SCHEMA_VERSION = 2;
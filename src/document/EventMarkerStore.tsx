@@ -72,7 +72,7 @@ export const EventMarkerDataStore = types
     deserialize(ser: EventMarkerData) {
       const points = self.getPath().params.waypoints;
       self.target = savedWaypointIdToWaypointId(ser.target, points);
-      self.targetTimestamp = self.targetTimestamp ?? undefined;
+      self.targetTimestamp = ser.targetTimestamp ?? undefined;
       self.offset.deserialize(ser.offset);
     },
     setTarget(target: WaypointUUID) {
@@ -9,6 +9,7 @@ export const DEFAULT_WAYPOINT: Waypoint<number> = {
   x: 0,
   y: 0,
   heading: 0,
+  pose: null,
   fixTranslation: true,
   fixHeading: true,
   intervals: 40,
@@ -23,6 +24,7 @@ export const HolonomicWaypointStore = types
     x: ExpressionStore,
     y: ExpressionStore,
     heading: ExpressionStore,
+    pose: types.maybeNull(types.string),
     fixTranslation: true,
     fixHeading: true,
     intervals: 40,
@@ -52,6 +54,7 @@ export const HolonomicWaypointStore = types
           x: self.x.serialize,
           y: self.y.serialize,
           heading: self.heading.serialize,
+          pose: self.pose,
           fixTranslation: self.fixTranslation,
           fixHeading: self.fixHeading,
           intervals: self.intervals,
@@ -81,6 +84,7 @@ export const HolonomicWaypointStore = types
         self.x.deserialize(point.x);
         self.y.deserialize(point.y);
         self.heading.deserialize(point.heading);
+        self.pose = point.pose ?? null;
         self.fixTranslation = point.fixTranslation;
         self.fixHeading = point.fixHeading;
         self.intervals = point.intervals;
@@ -90,6 +94,9 @@ export const HolonomicWaypointStore = types
       setFixTranslation(fixTranslation: boolean) {
         self.fixTranslation = fixTranslation;
       },
+      setPose(pose: string | null) {
+        self.pose = pose;
+      },
       setFixHeading(fixHeading: boolean) {
         self.fixHeading = fixHeading;
       },
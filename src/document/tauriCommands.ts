@@ -1,5 +1,14 @@
 import { invoke } from "@tauri-apps/api";
-import { Expr, Project, RobotConfig, Trajectory } from "./2025/DocumentTypes";
+import {
+  DimensionError,
+  Expr,
+  Lint,
+  Project,
+  RobotConfig,
+  Trajectory,
+  VariableRename,
+  Variables
+} from "./2025/DocumentTypes";
 import { OpenFilePayload } from "./DocumentManager";
 
 export const Commands = {
@@ -129,6 +138,50 @@ export const Commands = {
    */
   trajectoryUpToDate: (trajectory: Trajectory) =>
     invoke<boolean>("trajectory_up_to_date", { trajectory }),
+
+  /**
+   * Checks that every expression in the project and trajectory has the dimension of the field it is used in.
+   * @param project The `Project` holding the robot config and variables
+   * @param trajectory The `Trajectory` to check
+   * @returns One entry per mismatched expression, with its JSON path. Empty if all dimensions match.
+   */
+  checkDimensions: (project: Project, trajectory: Trajectory) =>
+    invoke<DimensionError[]>("check_dimensions", { project, trajectory }),
+  /**
+   * Checks the trajectory parameters for constraints that would be ignored or can't be satisfied, without generating.
+   * @param project The `Project` holding the robot config
+   * @param trajectory The `Trajectory` to check
+   * @returns The problems found, empty if there are none.
+   */
+  lintTrajectory: (project: Project, trajectory: Trajectory) =>
+    invoke<Lint[]>("lint_trajectory", { project, trajectory }),
+  /**
+   * Updates every trajectory in the deploy directory whose waypoints are bound to a pose variable.
+   * @param variables The project `Variables` holding the current poses
+   * @returns The names of the trajectories that changed and need to be regenerated.
+   */
+  refreshPoseLinks: (variables: Variables) =>
+    invoke<string[]>("refresh_pose_links", { variables }),
+  /**
+   * Renames a variable and rewrites every expression referencing it in the project and all trajectories.
+   * @param project The current `Project`
+   * @param oldName The current name of the variable
+   * @param newName The new name of the variable
+   * @param dryRun If true, no files are written
+   * @returns The renamed `Project` and the names of the trajectories that reference the variable.
+   */
+  renameVariable: (
+    project: Project,
+    oldName: string,
+    newName: string,
+    dryRun: boolean
+  ) =>
+    invoke<VariableRename>("rename_variable", {
+      project,
+      oldName,
+      newName,
+      dryRun
+    }),
   /**
    * If the application was opened via CLI and a file was specified, this will return the path of that file.
    *
@@ -25,6 +25,13 @@ LOCATIONS: list[Location] = [
         template=lambda length, width: f"""// Auto-generated by update_field_dimensions.py
 export const FIELD_LENGTH = {length};
 export const FIELD_WIDTH = {width};""",
+    ),
+    # Choreo backend
+    Location(
+        relative_path=Path("src-core/src/spec/field_dimensions.rs"),
+        template=lambda length, width: f"""// Auto-generated by update_field_dimensions.py
+pub const FIELD_LENGTH: f64 = {length};
+pub const FIELD_WIDTH: f64 = {width};""",
     ),
     # Java ChoreoLib
     Location(